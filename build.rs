@@ -1,3 +1,37 @@
+use std::{fs, path::Path, process::Command};
+
 fn main() {
+    capture_git_commit();
+
     tauri_build::build()
 }
+
+/// Exposes the current git commit to the app as `LOCALLM_GIT_COMMIT`.
+///
+/// This is `unknown` when building outside of a git checkout (e.g. from a source archive).
+fn capture_git_commit() {
+    let git_commit = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|git_commit| git_commit.trim().to_string())
+        .filter(|git_commit| !git_commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=LOCALLM_GIT_COMMIT={}", git_commit);
+
+    // Rebuild when the commit changes, either by switching branches or committing.
+    let git_head_path = Path::new(".git/HEAD");
+
+    if git_head_path.exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+
+        let git_head = fs::read_to_string(git_head_path).unwrap_or_default();
+
+        if let Some(git_ref) = git_head.strip_prefix("ref: ") {
+            println!("cargo:rerun-if-changed=.git/{}", git_ref.trim());
+        }
+    }
+}
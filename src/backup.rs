@@ -0,0 +1,883 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bollard::{
+    Docker,
+    query_parameters::{StartContainerOptionsBuilder, StopContainerOptionsBuilder},
+};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Wry};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::oneshot;
+use zip::{
+    CompressionMethod, DateTime, ZipArchive, ZipWriter, write::SimpleFileOptions,
+};
+
+use crate::{
+    container, docker,
+    error::AppError,
+    events::{BACKUP_PROGRESS_EVENT, DataArchiveProgress, RESTORE_PROGRESS_EVENT},
+};
+
+/// The name of the Open WebUI container.
+const OPEN_WEBUI_CONTAINER_NAME: &'static str = "local_llm_openwebui";
+
+/// The name of the Open WebUI database file in the data directory.
+const OPEN_WEBUI_DATABASE_FILE_NAME: &'static str = "webui.db";
+
+/// The path to the Open WebUI database in the Open WebUI container.
+const OPEN_WEBUI_DATABASE_CONTAINER_PATH: &'static str = "/app/backend/data/webui.db";
+
+/// The Python script that vacuums the database passed as its first argument.
+///
+/// The Open WebUI image doesn't include the `sqlite3` CLI, but it does include Python.
+/// The timeout is how many seconds to wait for Open WebUI to finish writing.
+const VACUUM_SCRIPT: &'static str = "import sqlite3, sys\nconnection = sqlite3.connect(sys.argv[1], timeout=30)\nconnection.execute('VACUUM')\nconnection.close()";
+
+/// The minimum number of bytes to process between progress events.
+const PROGRESS_EVENT_INTERVAL_BYTES: u64 = 4 * 1024 * 1024;
+
+/// The size of the buffer used when copying file contents.
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// The size at which a file needs ZIP64 extensions in a zip file.
+const ZIP64_FILE_SIZE_THRESHOLD: u64 = u32::MAX as u64;
+
+/// The result of backing up the Open WebUI data directory.
+#[derive(Serialize, Debug, Clone)]
+pub struct BackupResult {
+    /// The path to the backup.
+    #[serde(rename = "path")]
+    pub path: PathBuf,
+
+    /// The symlinks and special files that weren't included in the backup.
+    #[serde(rename = "skippedEntries")]
+    pub skipped_entries: Vec<String>,
+}
+
+/// Backs up the Open WebUI data directory to a zip file chosen by the user.
+///
+/// Open WebUI is stopped while the data is archived, so its database isn't written to
+/// mid-backup, and is started again afterwards (even if the backup fails).
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `data_dir` - The path to the Open WebUI data directory.
+/// * `stop_timeout_secs` - How long to wait for Open WebUI to stop.
+pub async fn backup_data(
+    app: &AppHandle<Wry>,
+    docker: &Docker,
+    data_dir: &Path,
+    stop_timeout_secs: u64,
+) -> Result<BackupResult, AppError> {
+    let (sender, receiver) = oneshot::channel();
+
+    app.dialog()
+        .file()
+        .set_title("Back up data")
+        .set_file_name(format!("localllm-chat-backup-{}.zip", get_timestamp()))
+        .add_filter("Zip archive", &["zip"])
+        .save_file(move |file_path| {
+            let _ = sender.send(file_path);
+        });
+
+    let archive_path = receiver
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| AppError::BackupError("The backup was cancelled".to_string()))?
+        .into_path()
+        .map_err(|e| AppError::BackupError(format!("The chosen location isn't a file path: {}", e)))?;
+
+    stop_openwebui(docker, stop_timeout_secs).await?;
+
+    println!("Backing up '{}' to '{}'", data_dir.display(), archive_path.display());
+
+    let backup_result = tokio::task::spawn_blocking({
+        let app = app.clone();
+        let data_dir = data_dir.to_path_buf();
+        let archive_path = archive_path.clone();
+
+        move || {
+            create_zip(
+                &data_dir,
+                &archive_path,
+                &mut build_progress_reporter(app, BACKUP_PROGRESS_EVENT),
+            )
+        }
+    })
+    .await;
+
+    start_openwebui(docker).await?;
+
+    match backup_result {
+        Ok(Ok(skipped_entries)) => Ok(BackupResult {
+            path: archive_path,
+            skipped_entries,
+        }),
+
+        Ok(Err(err)) => {
+            let _ = fs::remove_file(&archive_path);
+
+            Err(AppError::IOError(err))
+        }
+
+        Err(err) => Err(AppError::BackupError(format!(
+            "The task for creating the backup failed: {}",
+            err
+        ))),
+    }
+}
+
+/// Restores the Open WebUI data directory from a zip file chosen by the user.
+///
+/// The user is asked to confirm before the current data is replaced. The archive is
+/// extracted next to the data directory first, so the current data is kept if it fails.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `data_dir` - The path to the Open WebUI data directory.
+/// * `stop_timeout_secs` - How long to wait for Open WebUI to stop.
+pub async fn restore_data(
+    app: &AppHandle<Wry>,
+    docker: &Docker,
+    data_dir: &Path,
+    stop_timeout_secs: u64,
+) -> Result<PathBuf, AppError> {
+    let (sender, receiver) = oneshot::channel();
+
+    app.dialog()
+        .file()
+        .set_title("Restore data")
+        .add_filter("Zip archive", &["zip"])
+        .pick_file(move |file_path| {
+            let _ = sender.send(file_path);
+        });
+
+    let archive_path = receiver
+        .await
+        .ok()
+        .flatten()
+        .ok_or_else(|| AppError::BackupError("The restore was cancelled".to_string()))?
+        .into_path()
+        .map_err(|e| AppError::BackupError(format!("The chosen file isn't a file path: {}", e)))?;
+
+    let (sender, receiver) = oneshot::channel();
+
+    app.dialog()
+        .message(format!(
+            "Restoring from '{}' replaces all of the current chats, settings, and uploads. This can't be undone.\n\nOpen WebUI will be restarted.",
+            archive_path.display()
+        ))
+        .kind(MessageDialogKind::Warning)
+        .title("Restore data")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Restore".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = sender.send(confirmed);
+        });
+
+    if !receiver.await.unwrap_or(false) {
+        return Err(AppError::BackupError("The restore was cancelled".to_string()));
+    }
+
+    stop_openwebui(docker, stop_timeout_secs).await?;
+
+    println!("Restoring '{}' from '{}'", data_dir.display(), archive_path.display());
+
+    let restore_result = tokio::task::spawn_blocking({
+        let app = app.clone();
+        let data_dir = data_dir.to_path_buf();
+        let archive_path = archive_path.clone();
+
+        move || {
+            replace_data_dir(
+                &archive_path,
+                &data_dir,
+                &mut build_progress_reporter(app, RESTORE_PROGRESS_EVENT),
+            )
+        }
+    })
+    .await;
+
+    start_openwebui(docker).await?;
+
+    match restore_result {
+        Ok(Ok(())) => Ok(archive_path),
+
+        Ok(Err(err)) => Err(AppError::IOError(err)),
+
+        Err(err) => Err(AppError::BackupError(format!(
+            "The task for restoring the backup failed: {}",
+            err
+        ))),
+    }
+}
+
+/// The size of the Open WebUI database before and after it was vacuumed.
+#[derive(Serialize, Debug, Clone)]
+pub struct VacuumResult {
+    /// The size of the database before it was vacuumed, in bytes.
+    #[serde(rename = "sizeBeforeBytes")]
+    pub size_before_bytes: u64,
+
+    /// The size of the database after it was vacuumed, in bytes.
+    #[serde(rename = "sizeAfterBytes")]
+    pub size_after_bytes: u64,
+}
+
+/// Compacts the Open WebUI database with SQLite's `VACUUM`, after the user confirms.
+///
+/// The database is vacuumed from inside the running Open WebUI container. `VACUUM` holds
+/// an exclusive lock, so Open WebUI's writes wait for it to finish. If Open WebUI is in
+/// the middle of a long write, the vacuum gives up and nothing is changed.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `data_dir` - The path to the Open WebUI data directory.
+pub async fn vacuum_database(
+    app: &AppHandle<Wry>,
+    docker: &Docker,
+    data_dir: &Path,
+) -> Result<VacuumResult, AppError> {
+    let database_path = data_dir.join(OPEN_WEBUI_DATABASE_FILE_NAME);
+
+    let size_before_bytes = fs::metadata(&database_path)
+        .map_err(|e| AppError::IOError(e))?
+        .len();
+
+    let (sender, receiver) = oneshot::channel();
+
+    app.dialog()
+        .message(
+            "Compacting the database can take a while for a large database, and Open WebUI may be unresponsive until it's done.\n\nBack up your data first, in case it's interrupted.",
+        )
+        .kind(MessageDialogKind::Warning)
+        .title("Compact database")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Compact".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = sender.send(confirmed);
+        });
+
+    if !receiver.await.unwrap_or(false) {
+        return Err(AppError::BackupError("Compacting the database was cancelled".to_string()));
+    }
+
+    println!("Vacuuming '{}'", database_path.display());
+
+    let exec_result = container::exec_in_container(
+        docker,
+        OPEN_WEBUI_CONTAINER_NAME,
+        vec![
+            "python3".to_string(),
+            "-c".to_string(),
+            VACUUM_SCRIPT.to_string(),
+            OPEN_WEBUI_DATABASE_CONTAINER_PATH.to_string(),
+        ],
+    )
+    .await?;
+
+    if exec_result.stderr.contains("database is locked") {
+        return Err(AppError::BackupError(
+            "The database is in use by Open WebUI, so it wasn't compacted. Try again when Open WebUI is idle."
+                .to_string(),
+        ));
+    }
+
+    if exec_result.exit_code != Some(0) {
+        return Err(AppError::BackupError(format!(
+            "Failed to compact the database: {}",
+            exec_result.stderr.trim()
+        )));
+    }
+
+    let size_after_bytes = fs::metadata(&database_path)
+        .map_err(|e| AppError::IOError(e))?
+        .len();
+
+    println!(
+        "Vacuumed '{}' from {} bytes to {} bytes",
+        database_path.display(),
+        size_before_bytes,
+        size_after_bytes
+    );
+
+    Ok(VacuumResult {
+        size_before_bytes,
+        size_after_bytes,
+    })
+}
+
+/// Stops the Open WebUI container, so its data isn't being written to.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `stop_timeout_secs` - How long to wait for Open WebUI to stop before it's killed.
+async fn stop_openwebui(docker: &Docker, stop_timeout_secs: u64) -> Result<(), AppError> {
+    println!("Stopping container '{}'", OPEN_WEBUI_CONTAINER_NAME);
+
+    let stop_container_opts = StopContainerOptionsBuilder::new()
+        .t(stop_timeout_secs as i32)
+        .build();
+
+    match docker
+        .stop_container(OPEN_WEBUI_CONTAINER_NAME, Some(stop_container_opts))
+        .await
+    {
+        // A 304 means the container is already stopped.
+        Ok(_)
+        | Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 304, ..
+        }) => Ok(()),
+
+        Err(err) => Err(docker::map_docker_error(docker, err)),
+    }
+}
+
+/// Starts the Open WebUI container again.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+async fn start_openwebui(docker: &Docker) -> Result<(), AppError> {
+    println!("Starting container '{}'", OPEN_WEBUI_CONTAINER_NAME);
+
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
+
+    match docker
+        .start_container(OPEN_WEBUI_CONTAINER_NAME, Some(start_container_opts))
+        .await
+    {
+        // A 304 means the container is already running.
+        Ok(_)
+        | Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 304, ..
+        }) => Ok(()),
+
+        Err(err) => Err(docker::map_docker_error(docker, err)),
+    }
+}
+
+/// Builds a callback that emits progress events, throttled to avoid flooding the frontend.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `event` - The name of the event to emit.
+fn build_progress_reporter(app: AppHandle<Wry>, event: &'static str) -> impl FnMut(u64, u64) {
+    let mut last_reported_bytes: Option<u64> = None;
+
+    move |bytes_processed, total_bytes| {
+        let should_report = match last_reported_bytes {
+            Some(last_reported_bytes) => {
+                bytes_processed >= total_bytes
+                    || bytes_processed - last_reported_bytes >= PROGRESS_EVENT_INTERVAL_BYTES
+            }
+
+            None => true,
+        };
+
+        if should_report {
+            last_reported_bytes = Some(bytes_processed);
+
+            let _ = app.emit(
+                event,
+                DataArchiveProgress {
+                    bytes_processed,
+                    total_bytes,
+                },
+            );
+        }
+    }
+}
+
+/// Replaces the data directory with the contents of a zip file.
+///
+/// The archive is extracted to a sibling directory first, which is then swapped in.
+/// If anything fails before the swap, the current data directory is left untouched.
+///
+/// # Arguments
+///
+/// * `archive_path` - The path to the zip file.
+/// * `data_dir` - The path to the data directory.
+/// * `on_progress` - Called with the number of bytes extracted so far and the total.
+fn replace_data_dir(
+    archive_path: &Path,
+    data_dir: &Path,
+    on_progress: &mut impl FnMut(u64, u64),
+) -> io::Result<()> {
+    let restore_dir = data_dir.with_extension("restoring");
+    let previous_dir = data_dir.with_extension("previous");
+
+    // Clean up anything left behind by an earlier restore that was interrupted.
+    for leftover_dir in [&restore_dir, &previous_dir] {
+        if leftover_dir.exists() {
+            fs::remove_dir_all(leftover_dir)?;
+        }
+    }
+
+    if let Err(err) = extract_zip(archive_path, &restore_dir, on_progress) {
+        let _ = fs::remove_dir_all(&restore_dir);
+
+        return Err(err);
+    }
+
+    fs::rename(data_dir, &previous_dir)?;
+
+    if let Err(err) = fs::rename(&restore_dir, data_dir) {
+        let _ = fs::rename(&previous_dir, data_dir);
+
+        return Err(err);
+    }
+
+    // Files created by the container may not be removable by the current user,
+    // so a failure here is only logged. It's retried on the next restore.
+    if let Err(err) = fs::remove_dir_all(&previous_dir) {
+        println!(
+            "Failed to remove the previous data directory '{}': {}",
+            previous_dir.display(),
+            err
+        );
+    }
+
+    Ok(())
+}
+
+/// A file or directory to add to a zip file.
+struct ArchiveSource {
+    /// The path of the file or directory on disk.
+    path: PathBuf,
+
+    /// The name of the entry in the zip file, using `/` as the separator.
+    name: String,
+
+    /// The size of the file in bytes, or `0` for a directory.
+    size: u64,
+
+    /// The time the file or directory was last modified.
+    modified: Option<SystemTime>,
+}
+
+/// Archives the contents of a directory into a zip file.
+///
+/// ZIP64 is used when it's needed, so files and archives larger than 4 GiB and more than
+/// 65,535 entries are supported. Symlinks and special files (e.g. sockets) are skipped.
+///
+/// Returns the names of the entries that were skipped.
+///
+/// # Arguments
+///
+/// * `source_dir` - The directory to archive.
+/// * `archive_path` - The path to write the zip file to.
+/// * `on_progress` - Called with the number of bytes archived so far and the total.
+fn create_zip(
+    source_dir: &Path,
+    archive_path: &Path,
+    on_progress: &mut impl FnMut(u64, u64),
+) -> io::Result<Vec<String>> {
+    let mut sources = Vec::new();
+    let mut skipped_entries = Vec::new();
+    collect_archive_sources(source_dir, "", &mut sources, &mut skipped_entries)?;
+
+    let total_bytes = sources.iter().map(|source| source.size).sum::<u64>();
+    let mut bytes_processed = 0;
+
+    on_progress(bytes_processed, total_bytes);
+
+    let mut archive = ZipWriter::new(BufWriter::new(File::create(archive_path)?));
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+
+    for source in sources {
+        let options = SimpleFileOptions::default()
+            .last_modified_time(to_zip_date_time(source.modified))
+            // Without this, files over 4 GiB fail once their size is known.
+            .large_file(source.size >= ZIP64_FILE_SIZE_THRESHOLD);
+
+        if source.name.ends_with('/') {
+            archive.add_directory(source.name, options)?;
+
+            continue;
+        }
+
+        archive.start_file(
+            source.name,
+            options.compression_method(CompressionMethod::Deflated),
+        )?;
+
+        let mut file = File::open(&source.path)?;
+
+        loop {
+            let read = file.read(&mut buffer)?;
+
+            if read == 0 {
+                break;
+            }
+
+            archive.write_all(&buffer[..read])?;
+
+            bytes_processed += read as u64;
+            on_progress(bytes_processed, total_bytes);
+        }
+    }
+
+    archive.finish()?.flush()?;
+
+    on_progress(total_bytes, total_bytes);
+
+    Ok(skipped_entries)
+}
+
+/// Recursively collects the files and directories in a directory to add to a zip file.
+///
+/// # Arguments
+///
+/// * `dir` - The directory to collect from.
+/// * `name_prefix` - The prefix for the entry names (e.g. `uploads/`).
+/// * `sources` - The list to add the files and directories to.
+/// * `skipped_entries` - The list to add the names of symlinks and special files to.
+fn collect_archive_sources(
+    dir: &Path,
+    name_prefix: &str,
+    sources: &mut Vec<ArchiveSource>,
+    skipped_entries: &mut Vec<String>,
+) -> io::Result<()> {
+    for dir_entry in fs::read_dir(dir)? {
+        let dir_entry = dir_entry?;
+        let metadata = fs::symlink_metadata(dir_entry.path())?;
+
+        let name = format!("{}{}", name_prefix, dir_entry.file_name().to_string_lossy());
+
+        if metadata.is_dir() {
+            let dir_name = format!("{}/", name);
+
+            sources.push(ArchiveSource {
+                path: dir_entry.path(),
+                name: dir_name.clone(),
+                size: 0,
+                modified: metadata.modified().ok(),
+            });
+
+            collect_archive_sources(&dir_entry.path(), &dir_name, sources, skipped_entries)?;
+        } else if metadata.is_file() {
+            sources.push(ArchiveSource {
+                path: dir_entry.path(),
+                name,
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+            });
+        } else {
+            println!("Skipping '{}', since it isn't a file or directory", name);
+
+            skipped_entries.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Extracts a zip file into a directory.
+///
+/// Entries that would be extracted outside of the directory (e.g. `../file`) are rejected,
+/// and symlinks are skipped.
+///
+/// # Arguments
+///
+/// * `archive_path` - The path to the zip file.
+/// * `dest_dir` - The directory to extract to. It's created if it doesn't exist.
+/// * `on_progress` - Called with the number of bytes extracted so far and the total.
+fn extract_zip(
+    archive_path: &Path,
+    dest_dir: &Path,
+    on_progress: &mut impl FnMut(u64, u64),
+) -> io::Result<()> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(archive_path)?))
+        .map_err(|e| invalid_zip_error(&e.to_string()))?;
+
+    let mut total_bytes = 0;
+
+    for index in 0..archive.len() {
+        total_bytes += archive.by_index_raw(index)?.size();
+    }
+
+    let mut bytes_processed = 0;
+
+    on_progress(bytes_processed, total_bytes);
+
+    fs::create_dir_all(dest_dir)?;
+
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let entry_path = dest_dir.join(get_safe_entry_path(entry.name())?);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&entry_path)?;
+
+            continue;
+        }
+
+        if entry.is_symlink() {
+            println!("Skipping '{}', since it's a symlink", entry.name());
+
+            continue;
+        }
+
+        if let Some(parent_dir) = entry_path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        }
+
+        let mut file = BufWriter::new(File::create(&entry_path)?);
+
+        // The entry's CRC-32 is checked once it's been read to the end.
+        loop {
+            let read = entry.read(&mut buffer)?;
+
+            if read == 0 {
+                break;
+            }
+
+            file.write_all(&buffer[..read])?;
+
+            bytes_processed += read as u64;
+            on_progress(bytes_processed, total_bytes);
+        }
+
+        file.flush()?;
+    }
+
+    on_progress(total_bytes, total_bytes);
+
+    Ok(())
+}
+
+/// Gets the relative path to extract a zip entry to.
+///
+/// Returns an error if the path is absolute or would be outside of the destination directory.
+///
+/// # Arguments
+///
+/// * `entry_name` - The name of the entry in the zip file.
+fn get_safe_entry_path(entry_name: &str) -> io::Result<PathBuf> {
+    let entry_path = PathBuf::from(entry_name.replace('\\', "/"));
+
+    let is_safe = entry_path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+
+    match is_safe {
+        true => Ok(entry_path),
+        false => Err(invalid_zip_error(&format!(
+            "'{}' would be extracted outside of the data directory",
+            entry_name
+        ))),
+    }
+}
+
+/// Builds an error for a zip file that can't be read.
+///
+/// # Arguments
+///
+/// * `message` - The reason the zip file can't be read.
+fn invalid_zip_error(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("The backup isn't a valid zip file: {}", message),
+    )
+}
+
+/// The UTC date and time parts of a timestamp.
+struct DateTimeParts {
+    /// The year (e.g. `2025`).
+    year: i64,
+
+    /// The month, from `1` to `12`.
+    month: u32,
+
+    /// The day of the month, from `1` to `31`.
+    day: u32,
+
+    /// The hour, from `0` to `23`.
+    hour: u32,
+
+    /// The minute, from `0` to `59`.
+    minute: u32,
+
+    /// The second, from `0` to `59`.
+    second: u32,
+}
+
+impl DateTimeParts {
+    /// Splits a time into its UTC date and time parts.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time to split.
+    fn from_system_time(time: SystemTime) -> Self {
+        let secs = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let days = (secs / 86400) as i64;
+        let secs_of_day = (secs % 86400) as u32;
+
+        // Converts the days since the Unix epoch to a civil date (Howard Hinnant's `civil_from_days`).
+        let shifted_days = days + 719468;
+        let era = shifted_days.div_euclid(146097);
+        let day_of_era = shifted_days.rem_euclid(146097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = match shifted_month < 10 {
+            true => shifted_month + 3,
+            false => shifted_month - 9,
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: secs_of_day / 3600,
+            minute: secs_of_day % 3600 / 60,
+            second: secs_of_day % 60,
+        }
+    }
+}
+
+/// Gets the current UTC time as a timestamp for file names (e.g. `20250101-120000`).
+fn get_timestamp() -> String {
+    let now = DateTimeParts::from_system_time(SystemTime::now());
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        now.year, now.month, now.day, now.hour, now.minute, now.second
+    )
+}
+
+/// Converts a modification time to the date and time used in zip files.
+///
+/// Times outside of what zip files support (1980 to 2107), or unknown times,
+/// are clamped to the start of 1980.
+///
+/// # Arguments
+///
+/// * `modified` - The modification time.
+fn to_zip_date_time(modified: Option<SystemTime>) -> DateTime {
+    let parts = DateTimeParts::from_system_time(modified.unwrap_or(UNIX_EPOCH));
+
+    // Zip files store the seconds divided by two.
+    DateTime::from_date_and_time(
+        u16::try_from(parts.year).unwrap_or_default(),
+        parts.month as u8,
+        parts.day as u8,
+        parts.hour as u8,
+        parts.minute as u8,
+        (parts.second / 2 * 2) as u8,
+    )
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn create_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("localllm-chat-backup-{}-{}", name, std::process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn backups_can_be_extracted() {
+        let test_dir = create_test_dir("roundtrip");
+        let source_dir = test_dir.join("source");
+        let dest_dir = test_dir.join("dest");
+        let archive_path = test_dir.join("backup.zip");
+
+        fs::create_dir_all(source_dir.join("uploads/nested")).unwrap();
+        fs::create_dir_all(source_dir.join("empty")).unwrap();
+        fs::write(source_dir.join("webui.db"), vec![7u8; 3 * COPY_BUFFER_SIZE + 5]).unwrap();
+        fs::write(source_dir.join("uploads/nested/notes.txt"), "Hello, world!").unwrap();
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(source_dir.join("webui.db"), source_dir.join("link.db"))
+            .unwrap();
+
+        let mut last_progress = (0, 0);
+        let skipped_entries = create_zip(&source_dir, &archive_path, &mut |done, total| {
+            last_progress = (done, total);
+        })
+        .unwrap();
+
+        let total_bytes = 3 * COPY_BUFFER_SIZE as u64 + 18;
+        assert_eq!(last_progress, (total_bytes, total_bytes));
+
+        #[cfg(unix)]
+        assert_eq!(skipped_entries, vec!["link.db".to_string()]);
+
+        extract_zip(&archive_path, &dest_dir, &mut |_, _| {}).unwrap();
+
+        assert_eq!(
+            fs::read(dest_dir.join("webui.db")).unwrap(),
+            vec![7u8; 3 * COPY_BUFFER_SIZE + 5]
+        );
+        assert_eq!(
+            fs::read_to_string(dest_dir.join("uploads/nested/notes.txt")).unwrap(),
+            "Hello, world!"
+        );
+        assert!(dest_dir.join("empty").is_dir());
+        assert!(!dest_dir.join("link.db").exists());
+
+        let _ = fs::remove_dir_all(&test_dir);
+    }
+
+    #[test]
+    fn entries_outside_the_data_dir_are_rejected() {
+        assert!(get_safe_entry_path("uploads/file.txt").is_ok());
+        assert!(get_safe_entry_path("../file.txt").is_err());
+        assert!(get_safe_entry_path("/etc/passwd").is_err());
+        assert!(get_safe_entry_path("uploads\\..\\..\\file.txt").is_err());
+    }
+
+    #[test]
+    fn modification_times_are_converted_to_zip_times() {
+        let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_001);
+        let date_time = to_zip_date_time(Some(modified));
+
+        assert_eq!(
+            (date_time.year(), date_time.month(), date_time.day()),
+            (2023, 11, 14)
+        );
+        assert_eq!(
+            (date_time.hour(), date_time.minute(), date_time.second()),
+            (22, 13, 20)
+        );
+        assert_eq!(to_zip_date_time(Some(UNIX_EPOCH)), DateTime::default());
+        assert_eq!(to_zip_date_time(None), DateTime::default());
+    }
+}
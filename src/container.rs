@@ -1,480 +1,4234 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use bollard::{
     Docker,
+    container::LogOutput,
+    exec::{CreateExecOptions, StartExecResults},
     models::*,
     query_parameters::{
-        CreateContainerOptionsBuilder, CreateImageOptionsBuilder, ListContainersOptionsBuilder,
-        ListNetworksOptionsBuilder, RemoveContainerOptionsBuilder, StartContainerOptionsBuilder,
-        StopContainerOptionsBuilder,
+        CreateContainerOptionsBuilder, CreateImageOptionsBuilder, ImportImageOptionsBuilder,
+        InspectContainerOptions, InspectContainerOptionsBuilder, InspectNetworkOptions,
+        ListContainersOptionsBuilder, ListNetworksOptionsBuilder, LogsOptionsBuilder,
+        RemoveContainerOptionsBuilder, RemoveImageOptions, RemoveImageOptionsBuilder,
+        StartContainerOptionsBuilder, StopContainerOptionsBuilder, TagImageOptionsBuilder,
+        WaitContainerOptions,
     },
     secret::{ContainerCreateBody, NetworkCreateRequest},
 };
-use futures_util::StreamExt;
+use bytes::Bytes;
+use futures_util::{
+    Stream, StreamExt,
+    future::{Abortable, Aborted},
+};
+use serde::Serialize;
+use tokio::io::AsyncReadExt;
 
 use crate::{
-    config::{LlmChatConfig, LlmChatConfigExtraBackendService},
+    config::{
+        BackendServiceHostVolumePathBinding, EMBEDDINGS_BACKEND_SERVICE_NAME,
+        EmbeddingsBackendConfig, GpuConfig, LlmChatConfig,
+        LlmChatConfigExtraBackendService, REVERSE_PROXY_SERVICE_NAME, RegistryMirror,
+        ReverseProxyConfig, Secrets, UNCONFINED_SECURITY_PROFILE,
+    },
+    docker,
     error::AppError,
+    events::{ImageLoadProgress, PullProgress},
+    progress::{ProgressReporter, PullProgressAccumulator},
+    state::{ActivePulls, RuntimeState},
+    utils,
 };
 
 /// The Open WebUI container image name and tag.
 const OPEN_WEBUI_IMAGE_BASE: &'static str = "ghcr.io/open-webui/open-webui";
 
+/// The path the data directory is mounted to in the Open WebUI container.
+const OPEN_WEBUI_DATA_PATH: &'static str = "/app/backend/data";
+
 /// The Apache Tika container image name and tag.
 const TIKA_IMAGE_BASE: &'static str = "docker.io/apache/tika";
 
-/// Pulls the required container images.
+/// The default port on the host that Open WebUI is bound to.
+pub const OPEN_WEBUI_HOST_PORT: u16 = 11690;
+
+/// The label added to every container managed by the application.
+pub const MANAGED_LABEL_KEY: &'static str = "online.smalls.locallm.managed";
+
+/// Builds the labels to add to a container or network.
+///
+/// User supplied labels are added first and then the management label is added,
+/// so the management label can't be overridden by the config.
 ///
 /// # Arguments
 ///
-/// * `app_config` - The application configuration.
-pub async fn pull_required_images(app_config: &LlmChatConfig) -> Result<(), AppError> {
-    let open_webui_image = format!(
-        "{}:{}",
-        OPEN_WEBUI_IMAGE_BASE, app_config.openwebui_image_tag
-    );
-    let tika_image = format!("{}:{}", TIKA_IMAGE_BASE, app_config.tika_image_tag);
-
-    let mut images = vec![open_webui_image, tika_image];
+/// * `user_labels` - The labels supplied in the config, if any.
+fn build_container_labels(user_labels: &Option<HashMap<String, String>>) -> HashMap<String, String> {
+    let mut labels = HashMap::<String, String>::new();
 
-    if let Some(extra_services) = app_config.extra_backend_services.clone() {
-        for extra_service in extra_services {
-            images.push(extra_service.image);
+    if let Some(user_labels) = user_labels {
+        for (key, value) in user_labels {
+            labels.insert(key.clone(), value.clone());
         }
     }
 
-    for image in images {
-        pull_image(&image).await?;
-    }
+    labels.insert(MANAGED_LABEL_KEY.to_string(), "true".to_string());
 
-    Ok(())
+    labels
 }
 
-/// Pull a container image.
+/// Gets the Open WebUI image name and tag.
 ///
 /// # Arguments
 ///
-/// * `image` - The image to pull.
-async fn pull_image(image: &str) -> Result<(), AppError> {
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
-
-    let create_image_opts = CreateImageOptionsBuilder::new().from_image(image).build();
-
-    let mut pull_stream = docker.create_image(Some(create_image_opts), None, None);
+/// * `app_config` - The application configuration.
+pub fn get_openwebui_image(app_config: &LlmChatConfig) -> String {
+    format!("{}:{}", OPEN_WEBUI_IMAGE_BASE, app_config.openwebui_image_tag)
+}
 
-    while let Some(msg) = pull_stream.next().await {
-        match msg {
-            Ok(msg) => println!("{:?}: {:?}", msg.id, msg.status),
+/// Gets the Apache Tika image name and tag.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+pub fn get_tika_image(app_config: &LlmChatConfig) -> String {
+    format!("{}:{}", TIKA_IMAGE_BASE, app_config.tika_image_tag)
+}
 
-            Err(err) => return Err(AppError::DockerError(err)),
-        }
-    }
+/// A container image required by the application.
+#[derive(Debug, Clone)]
+pub struct RequiredImage {
+    /// The image name and tag.
+    pub image: String,
 
-    Ok(())
+    /// The platform to pull the image for, if any.
+    pub platform: Option<String>,
 }
 
-/// Creates the infrastructure needed to run the application.
+/// Gets the container images required by the application.
 ///
-/// This includes:
+/// # Arguments
 ///
-/// * The frontend and backend networks.
-/// * Running Open WebUI.
-/// * Running any backend services needed for Open WebUI.
-///   * For example, Apache Tika.
-pub async fn create_infrastructure(
-    app_config: &LlmChatConfig,
-    data_dir: &PathBuf,
-) -> Result<(), AppError> {
-    let _ = create_frontend_network().await?;
-    let _ = create_backend_network().await?;
+/// * `app_config` - The application configuration.
+pub fn get_required_images(app_config: &LlmChatConfig) -> Vec<RequiredImage> {
+    let open_webui_image = get_openwebui_image(app_config);
+    let tika_image = get_tika_image(app_config);
 
-    create_openwebui_container(app_config, data_dir).await?;
-    create_tika_container(app_config).await?;
+    let mut images = vec![
+        RequiredImage {
+            image: open_webui_image,
+            platform: app_config.platform.clone(),
+        },
+        RequiredImage {
+            image: tika_image,
+            platform: app_config.platform.clone(),
+        },
+    ];
 
-    if let Some(extra_services) = app_config.extra_backend_services.clone() {
-        let mut extra_services_tasks = Vec::with_capacity(extra_services.len());
+    if let Some(reverse_proxy) = &app_config.reverse_proxy {
+        images.push(RequiredImage {
+            image: reverse_proxy.image.clone(),
+            platform: reverse_proxy.platform.clone(),
+        });
+    }
 
-        for extra_service in extra_services {
-            extra_services_tasks.push(tokio::spawn(create_extra_service_container(extra_service.clone())));
-        }
+    if let Some(embeddings_backend) = &app_config.embeddings_backend {
+        images.push(RequiredImage {
+            image: embeddings_backend.image.clone(),
+            platform: embeddings_backend.platform.clone(),
+        });
+    }
 
-        for task in extra_services_tasks {
-            task.await.unwrap()?;
-        }
+    for extra_service in app_config.get_enabled_extra_services() {
+        images.push(RequiredImage {
+            image: extra_service.image,
+            platform: extra_service.platform,
+        });
     }
 
-    Ok(())
+    images
 }
 
-/// Creates the `local_llm_frontend` network with Docker (or any Docker-compatible API).
-async fn create_frontend_network() -> Result<NetworkCreateResponse, AppError> {
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+/// Whether a required image is present locally.
+#[derive(Serialize, Debug, Clone)]
+pub struct ImagePresence {
+    /// The image name and tag.
+    #[serde(rename = "image")]
+    pub image: String,
 
-    let mut driver_opts = HashMap::<String, String>::new();
-    driver_opts.insert(
-        "com.docker.network.bridge.host_binding_ipv4".to_string(),
-        "127.0.0.1".to_string(),
-    );
+    /// Whether the image is present locally.
+    #[serde(rename = "present")]
+    pub present: bool,
 
-    let network = docker
-        .create_network(NetworkCreateRequest {
-            name: "local_llm_frontend".to_string(),
-            driver: Some("bridge".to_string()),
-            options: Some(driver_opts),
-            ..Default::default()
-        })
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+    /// The local ID of the image, if it's present.
+    #[serde(rename = "imageId", skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<String>,
 
-    Ok(network)
+    /// The size of the image in bytes, if it's present.
+    #[serde(rename = "size", skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
 }
 
-/// Creates the `local_llm_backend` network with Docker (or any Docker-compatible API).
-async fn create_backend_network() -> Result<NetworkCreateResponse, AppError> {
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+/// Checks whether each required image is present locally.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn check_images_present(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+) -> Result<Vec<ImagePresence>, AppError> {
+    let required_images = get_required_images(app_config);
+    let mut images_presence = Vec::with_capacity(required_images.len());
 
-    let network = docker
-        .create_network(NetworkCreateRequest {
-            name: "local_llm_backend".to_string(),
-            ..Default::default()
-        })
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+    for required_image in required_images {
+        let image_presence = match docker.inspect_image(&required_image.image).await {
+            Ok(image_inspect) => ImagePresence {
+                image: required_image.image,
+                present: true,
+                image_id: image_inspect.id,
+                size: image_inspect.size,
+            },
 
-    Ok(network)
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => ImagePresence {
+                image: required_image.image,
+                present: false,
+                image_id: None,
+                size: None,
+            },
+
+            Err(err) => return Err(docker::map_docker_error(docker, err)),
+        };
+
+        images_presence.push(image_presence);
+    }
+
+    Ok(images_presence)
 }
 
-/// Creates and starts the Open WebUI container with Docker (or any Docker-compatible API).
+/// Records the IDs of the required images after a pull.
+///
+/// When `prune_old_images` is enabled, any image that was replaced by a newer
+/// pull is queued to be pruned.
 ///
 /// # Arguments
 ///
+/// * `docker` - The Docker client.
 /// * `app_config` - The application configuration.
-/// * `data_dir` - The host path to data directory to mount into the container.
-///
-/// # Notes
-///
-/// The name of the container will always be `local_llm_openwebui`.
-async fn create_openwebui_container(
+/// * `runtime_state` - The persisted runtime state.
+pub async fn track_replaced_images(
+    docker: &Docker,
     app_config: &LlmChatConfig,
-    data_dir: &PathBuf,
+    runtime_state: &mut RuntimeState,
 ) -> Result<(), AppError> {
-    let open_webui_image = format!(
-        "{}:{}",
-        OPEN_WEBUI_IMAGE_BASE, app_config.openwebui_image_tag
-    );
-    let data_dir = data_dir.to_string_lossy().to_string();
+    for image_presence in check_images_present(docker, app_config).await? {
+        let Some(image_id) = image_presence.image_id else {
+            continue;
+        };
 
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+        let previous_image_id = runtime_state
+            .image_ids
+            .insert(image_presence.image.clone(), image_id.clone());
 
-    let create_container_opts = CreateContainerOptionsBuilder::new()
-        .name("local_llm_openwebui")
-        .build();
+        if let Some(previous_image_id) = previous_image_id {
+            let should_prune = app_config.prune_old_images
+                && previous_image_id != image_id
+                && !runtime_state.images_to_prune.contains(&previous_image_id);
 
-    let container_env = vec![
-        "ENV=dev".to_string(),
-        "WEBUI_AUTH=false".to_string(),
-        //"WEB_LOADER_ENGINE=playwright".to_string(),
-        //"PLAYWRIGHT_WS_URI=ws://playwright:3000".to_string(),
-    ];
+            if should_prune {
+                println!(
+                    "Image '{}' was updated, queueing '{}' to be pruned",
+                    image_presence.image, previous_image_id
+                );
 
-    let mut networks = HashMap::<String, EndpointSettings>::new();
-    networks.insert(
-        "local_llm_frontend".to_string(),
-        EndpointSettings::default(),
-    );
-    networks.insert("local_llm_backend".to_string(), EndpointSettings::default());
+                runtime_state.images_to_prune.push(previous_image_id);
+            }
+        }
+    }
 
-    let networking_config = NetworkingConfig {
-        endpoints_config: Some(networks),
-    };
+    Ok(())
+}
 
-    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
-    container_ports.insert("8080/tcp".to_string(), HashMap::default());
+/// Removes the images queued to be pruned, if no container uses them.
+///
+/// Images that are still in use stay queued and are tried again on the next launch.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `runtime_state` - The persisted runtime state.
+pub async fn prune_replaced_images(
+    docker: &Docker,
+    runtime_state: &mut RuntimeState,
+) -> Result<(), AppError> {
+    if runtime_state.images_to_prune.is_empty() {
+        return Ok(());
+    }
 
-    let mut port_binds = HashMap::<String, Option<Vec<PortBinding>>>::new();
-    port_binds.insert(
-        "8080/tcp".to_string(),
-        Some(vec![PortBinding {
-            host_port: Some("11690".to_string()),
-            ..Default::default()
-        }]),
-    );
+    let list_containers_opts = ListContainersOptionsBuilder::new().all(true).build();
 
-    let host_config = HostConfig {
-        binds: Some(vec![format!("{}:/app/backend/data", data_dir)]),
-        port_bindings: Some(port_binds),
-        ..Default::default()
-    };
+    let used_image_ids: Vec<String> = docker
+        .list_containers(Some(list_containers_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?
+        .into_iter()
+        .filter_map(|container| container.image_id)
+        .collect();
 
-    let container_config = ContainerCreateBody {
-        image: Some(open_webui_image),
-        env: Some(container_env),
-        networking_config: Some(networking_config),
-        exposed_ports: Some(container_ports),
-        host_config: Some(host_config),
-        ..Default::default()
-    };
+    let mut still_queued = Vec::new();
 
-    docker
-        .create_container(Some(create_container_opts), container_config)
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+    for image_id in runtime_state.images_to_prune.drain(..) {
+        let is_in_use = used_image_ids.contains(&image_id)
+            || runtime_state.image_ids.values().any(|current_id| current_id == &image_id);
 
-    let start_container_opts = StartContainerOptionsBuilder::new().build();
+        if is_in_use {
+            println!("Not pruning image '{}', since it's still in use", image_id);
+            still_queued.push(image_id);
 
-    docker
-        .start_container("local_llm_openwebui", Some(start_container_opts))
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+            continue;
+        }
 
-    Ok(())
-}
+        let remove_image_opts = RemoveImageOptionsBuilder::new().build();
 
-/// Creates and starts the Apache Tika container with Docker (or any Docker-compatible API).
-///
-/// # Arguments
-///
-/// * `app_config` - The application configuration.
-///
-/// # Notes
-///
-/// The name of the container will always be `local_llm_tika`.
-async fn create_tika_container(app_config: &LlmChatConfig) -> Result<(), AppError> {
-    let tika_image = format!("{}:{}", TIKA_IMAGE_BASE, app_config.tika_image_tag);
+        match docker
+            .remove_image(&image_id, Some(remove_image_opts), None)
+            .await
+        {
+            Ok(removed_items) => {
+                for removed_item in removed_items {
+                    if let Some(untagged) = removed_item.untagged {
+                        println!("Pruned image: untagged '{}'", untagged);
+                    }
 
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+                    if let Some(deleted) = removed_item.deleted {
+                        println!("Pruned image: deleted '{}'", deleted);
+                    }
+                }
+            }
 
-    let create_container_opts = CreateContainerOptionsBuilder::new()
-        .name("local_llm_tika")
-        .build();
+            // The image was already removed outside of the app.
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => println!("Image '{}' was already removed", image_id),
 
-    let mut networks = HashMap::<String, EndpointSettings>::new();
-    networks.insert("local_llm_backend".to_string(), EndpointSettings::default());
+            Err(err) => {
+                println!("Failed to prune image '{}': {}", image_id, err);
+                still_queued.push(image_id);
+            }
+        }
+    }
 
-    let networking_config = NetworkingConfig {
-        endpoints_config: Some(networks),
-    };
+    runtime_state.images_to_prune = still_queued;
 
-    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
-    container_ports.insert("9998/tcp".to_string(), HashMap::default());
+    Ok(())
+}
 
-    let container_config = ContainerCreateBody {
-        image: Some(tika_image),
-        networking_config: Some(networking_config),
-        exposed_ports: Some(container_ports),
-        ..Default::default()
-    };
+/// The disk usage of a required image.
+#[derive(Serialize, Debug, Clone)]
+pub struct ImageDiskUsage {
+    /// The image name and tag.
+    #[serde(rename = "image")]
+    pub image: String,
 
-    docker
-        .create_container(Some(create_container_opts), container_config)
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+    /// The size of the image in bytes, or `0` if it isn't present.
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+}
 
-    let start_container_opts = StartContainerOptionsBuilder::new().build();
+/// The disk usage of a host volume binding for an extra backend service.
+#[derive(Serialize, Debug, Clone)]
+pub struct VolumeDiskUsage {
+    /// The name of the extra backend service, or `openwebui` for the `data_sub_mounts`.
+    #[serde(rename = "serviceName")]
+    pub service_name: String,
 
-    docker
-        .start_container("local_llm_tika", Some(start_container_opts))
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+    /// The path on the host.
+    #[serde(rename = "hostPath")]
+    pub host_path: String,
 
-    Ok(())
+    /// The size of the path in bytes, or `0` if it doesn't exist.
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
 }
 
-/// Creates and starts an extra backend container with Docker (or any Docker-compatible API).
-///
-/// # Arguments
-///
-/// * `extra_service` - The extra service config.
-///
-/// # Notes
-///
-/// The name of the container will always be `local_llm_{name}`.
-async fn create_extra_service_container(
-    extra_service: LlmChatConfigExtraBackendService,
-) -> Result<(), AppError> {
-    let container_name = format!("local_llm_{}", extra_service.name);
+/// The disk usage of the resources managed by the app.
+#[derive(Serialize, Debug, Clone)]
+pub struct DiskUsage {
+    /// The disk usage of each required image.
+    #[serde(rename = "images")]
+    pub images: Vec<ImageDiskUsage>,
 
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+    /// The size of the Open WebUI data directory in bytes.
+    #[serde(rename = "dataDirBytes")]
+    pub data_dir_bytes: u64,
 
-    let create_container_opts = CreateContainerOptionsBuilder::new()
-        .name(&container_name)
-        .build();
+    /// The disk usage of each host volume binding.
+    #[serde(rename = "volumes")]
+    pub volumes: Vec<VolumeDiskUsage>,
 
-    let container_env = extra_service.env.clone();
+    /// The total size of everything above in bytes.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+}
 
-    let mut networks = HashMap::<String, EndpointSettings>::new();
-    networks.insert("local_llm_backend".to_string(), EndpointSettings::default());
+/// Gets the disk usage of the resources managed by the app.
+///
+/// Images share layers, so the image sizes can add up to more than
+/// the space they actually use on disk.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The path to the Open WebUI data directory.
+pub async fn get_disk_usage(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+) -> Result<DiskUsage, AppError> {
+    let images = check_images_present(docker, app_config)
+        .await?
+        .into_iter()
+        .map(|image_presence| ImageDiskUsage {
+            image: image_presence.image,
+            size_bytes: image_presence.size.unwrap_or_default().max(0) as u64,
+        })
+        .collect::<Vec<ImageDiskUsage>>();
 
-    let networking_config = NetworkingConfig {
-        endpoints_config: Some(networks),
-    };
+    let mut volume_paths = Vec::<(String, String)>::new();
 
-    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
-    if let Some(ports) = extra_service.ports.clone() {
-        for port in ports {
-            container_ports.insert(port, HashMap::default());
-        }
+    for data_sub_mount in app_config.data_sub_mounts.iter().flatten() {
+        volume_paths.push(("openwebui".to_string(), data_sub_mount.host_path.clone()));
     }
 
-    let host_config = match &extra_service.volume_bindings {
-        Some(volume_bindings) => {
-            let mut host_binds: Vec<String> = vec![];
-
-            for volume in volume_bindings {
-                host_binds.push(format!(
-                    "{host_path}:{container_path}",
-                    host_path = volume.host_path,
-                    container_path = volume.container_path
-                ));
+    if let Some(extra_services) = &app_config.extra_backend_services {
+        for extra_service in extra_services {
+            for volume_binding in extra_service.volume_bindings.iter().flatten() {
+                volume_paths.push((extra_service.name.clone(), volume_binding.host_path.clone()));
             }
-
-            Some(HostConfig {
-                binds: Some(host_binds),
-                ..Default::default()
-            })
         }
+    }
 
-        None => None,
-    };
-
-    let container_config = ContainerCreateBody {
-        image: Some(extra_service.image.clone()),
-        cmd: extra_service.cmd.clone(),
-        env: container_env,
-        networking_config: Some(networking_config),
-        exposed_ports: Some(container_ports),
-        host_config: host_config,
-        user: extra_service.user.clone(),
-        working_dir: extra_service.working_directory.clone(),
-        ..Default::default()
-    };
+    // Walking the directories can take a while, so it's done off of the async runtime.
+    let data_dir = data_dir.clone();
+    let (data_dir_bytes, volumes) = tokio::task::spawn_blocking(move || {
+        let data_dir_bytes = utils::get_path_size(&data_dir);
 
-    docker
-        .create_container(Some(create_container_opts), container_config)
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+        let volumes = volume_paths
+            .into_iter()
+            .map(|(service_name, host_path)| VolumeDiskUsage {
+                size_bytes: utils::get_path_size(Path::new(&host_path)),
+                service_name,
+                host_path,
+            })
+            .collect::<Vec<VolumeDiskUsage>>();
 
-    let start_container_opts = StartContainerOptionsBuilder::new().build();
+        (data_dir_bytes, volumes)
+    })
+    .await
+    .map_err(|e| {
+        AppError::GenericError(format!("The task for measuring disk usage failed: {}", e))
+    })?;
 
-    docker
-        .start_container(&container_name, Some(start_container_opts))
-        .await
-        .map_err(|e| AppError::DockerError(e))?;
+    let total_bytes = images.iter().map(|image| image.size_bytes).sum::<u64>()
+        + data_dir_bytes
+        + volumes.iter().map(|volume| volume.size_bytes).sum::<u64>();
 
-    Ok(())
+    Ok(DiskUsage {
+        images,
+        data_dir_bytes,
+        volumes,
+        total_bytes,
+    })
 }
 
-/// Cleans up Docker (or any Docker-compatible API) resources created by the application.
+/// Pulls the required container images.
 ///
 /// # Arguments
 ///
+/// * `reporter` - Receives the progress of the pulls.
+/// * `docker` - The Docker client.
 /// * `app_config` - The application configuration.
-pub async fn cleanup_infrastructure(app_config: &LlmChatConfig) -> Result<(), AppError> {
-    println!("Deleting containers...");
-    delete_containers(app_config).await?;
+/// * `active_pulls` - Where the pulls are tracked, so they can be cancelled.
+pub async fn pull_required_images(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    active_pulls: &ActivePulls,
+) -> Result<(), AppError> {
+    let images = get_required_images(app_config);
+    let tika_image = get_tika_image(app_config);
 
-    println!("Deleting networks...");
-    delete_networks().await?;
+    let reporter = &PullProgressAccumulator::new(reporter, images.len());
 
-    Ok(())
-}
+    // Docker doesn't expose a way to limit the bandwidth of a pull,
+    // so the number of images pulled at once is bounded instead.
+    let mut pull_results = futures_util::stream::iter(images)
+        .map(|required_image| async move {
+            let pull_result = pull_image_with_mirrors(
+                reporter,
+                docker,
+                &required_image.image,
+                required_image.platform.as_deref(),
+                app_config.registry_mirrors.as_deref().unwrap_or_default(),
+                active_pulls,
+            )
+            .await;
 
-/// Delete the `local_llm_frontend` and `local_llm_backend` networks from Docker (or any Docker-compatible API).
-async fn delete_networks() -> Result<(), AppError> {
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+            (required_image.image, pull_result)
+        })
+        .buffer_unordered(app_config.pull_max_concurrency);
 
+    while let Some((image, pull_result)) = pull_results.next().await {
+        match pull_result {
+            Ok(_) => {}
+
+            // Open WebUI runs fine without Tika, so it's left to fail to start instead.
+            Err(err) if image == tika_image && !app_config.tika_required => {
+                reporter.log(&format!(
+                    "Warning: Failed to pull Tika, continuing without it: {}",
+                    err
+                ));
+            }
+
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(())
+}
+
+/// Pulls the Open WebUI image for the config.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `active_pulls` - Where the pull is tracked, so it can be cancelled.
+pub async fn pull_openwebui_image(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    active_pulls: &ActivePulls,
+) -> Result<(), AppError> {
+    pull_image_with_mirrors(
+        reporter,
+        docker,
+        &get_openwebui_image(app_config),
+        app_config.platform.as_deref(),
+        app_config.registry_mirrors.as_deref().unwrap_or_default(),
+        active_pulls,
+    )
+    .await
+}
+
+/// Pulls an image that isn't required by the config, like one of the `prewarm_images`.
+///
+/// The pull uses the configured platform and registry mirrors, the same as the required images.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `image` - The image to pull.
+/// * `active_pulls` - Where the pull is tracked, so it can be cancelled.
+pub async fn pull_optional_image(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    image: &str,
+    active_pulls: &ActivePulls,
+) -> Result<(), AppError> {
+    pull_image_with_mirrors(
+        reporter,
+        docker,
+        image,
+        app_config.platform.as_deref(),
+        app_config.registry_mirrors.as_deref().unwrap_or_default(),
+        active_pulls,
+    )
+    .await
+}
+
+/// The registry that images without an explicit registry are pulled from.
+const DOCKER_HUB_REGISTRY: &'static str = "docker.io";
+
+/// Pulls a container image, trying any matching registry mirrors first.
+///
+/// An image pulled from a mirror is tagged with its original name, so the
+/// containers don't need to know whether it came from a mirror.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `image` - The image to pull.
+/// * `platform` - The platform to pull the image for, if any.
+/// * `registry_mirrors` - The configured registry mirrors.
+/// * `active_pulls` - Where the pull is tracked, so it can be cancelled.
+async fn pull_image_with_mirrors(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    image: &str,
+    platform: Option<&str>,
+    registry_mirrors: &[RegistryMirror],
+    active_pulls: &ActivePulls,
+) -> Result<(), AppError> {
+    let (_active_pull_guard, abort_registration) = active_pulls.track(image);
+
+    // Dropping the pull closes the connection to the engine, which stops the pull there too.
+    let pull = Abortable::new(
+        pull_image_with_mirrors_uncancellable(reporter, docker, image, platform, registry_mirrors),
+        abort_registration,
+    );
+
+    match pull.await {
+        Ok(pull_result) => pull_result,
+
+        Err(Aborted) => {
+            reporter.log(&format!("Cancelled the pull of '{}'", image));
+
+            Err(AppError::ImagePullCancelled(image.to_string()))
+        }
+    }
+}
+
+/// Pulls a container image, trying any matching registry mirrors first, without tracking it.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `image` - The image to pull.
+/// * `platform` - The platform to pull the image for, if any.
+/// * `registry_mirrors` - The configured registry mirrors.
+async fn pull_image_with_mirrors_uncancellable(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    image: &str,
+    platform: Option<&str>,
+    registry_mirrors: &[RegistryMirror],
+) -> Result<(), AppError> {
+    for mirror_image in get_mirror_images(image, registry_mirrors) {
+        reporter.log(&format!("Pulling '{}' from mirror as '{}'", image, mirror_image));
+
+        // The progress is reported under the requested image, so a mirror that fails partway
+        // through and the pull that falls back from it are counted as a single image.
+        let pull_result = pull_image(reporter, docker, &mirror_image, image, platform).await;
+
+        if let Err(err) = pull_result {
+            reporter.log(&format!(
+                "Failed to pull from mirror, trying the next one: {}",
+                err
+            ));
+
+            continue;
+        }
+
+        let (repo, tag) = split_image_tag(image);
+        let tag_image_opts = TagImageOptionsBuilder::new().repo(repo).tag(tag).build();
+
+        docker
+            .tag_image(&mirror_image, Some(tag_image_opts))
+            .await
+            .map_err(|e| docker::map_docker_error(docker, e))?;
+
+        return Ok(());
+    }
+
+    pull_image(reporter, docker, image, image, platform).await
+}
+
+/// How often to report the progress of loading images from an archive.
+const IMAGE_LOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The size of the chunks an image archive is sent to Docker in.
+const IMAGE_LOAD_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Loads images from an archive created with `docker save`, for hosts that can't pull.
+///
+/// The load is bounded by `image_load_timeout_secs`, since Docker can stall on a large
+/// archive. Afterwards, the images the config expects are checked to be present.
+///
+/// Returns the required images that were loaded from the archive.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the load.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `archive_path` - The path to the image archive.
+pub async fn load_image_archive(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    archive_path: &Path,
+) -> Result<Vec<String>, AppError> {
+    let path = archive_path.display().to_string();
+    let image_load_failed = |detail: String| AppError::ImageLoadFailed {
+        path: path.clone(),
+        detail,
+    };
+
+    let archive = tokio::fs::File::open(archive_path)
+        .await
+        .map_err(|e| image_load_failed(e.to_string()))?;
+
+    let total_bytes = archive
+        .metadata()
+        .await
+        .map_err(|e| image_load_failed(e.to_string()))?
+        .len();
+
+    let bytes_loaded = Arc::new(AtomicU64::new(0));
+    let archive_stream = read_archive_chunks(archive, bytes_loaded.clone());
+
+    let import_image_opts = ImportImageOptionsBuilder::new().quiet(false).build();
+    let mut load_stream = docker.import_image_stream(import_image_opts, archive_stream, None);
+
+    let report_progress = || {
+        reporter.image_load_progress(ImageLoadProgress {
+            path: path.clone(),
+            bytes_loaded: bytes_loaded.load(Ordering::Relaxed),
+            total_bytes,
+        })
+    };
+
+    let mut loaded_images = Vec::<String>::new();
+
+    let load = async {
+        let mut progress_interval = tokio::time::interval(IMAGE_LOAD_PROGRESS_INTERVAL);
+
+        loop {
+            tokio::select! {
+                load_info = load_stream.next() => match load_info {
+                    None => return Ok(()),
+
+                    Some(Err(err)) => return Err(image_load_failed(err.to_string())),
+
+                    Some(Ok(load_info)) => {
+                        let error = load_info
+                            .error_detail
+                            .and_then(|error_detail| error_detail.message)
+                            .or(load_info.error);
+
+                        if let Some(error) = error {
+                            return Err(image_load_failed(error));
+                        }
+
+                        let loaded_image = load_info
+                            .stream
+                            .as_deref()
+                            .and_then(|stream| stream.trim().strip_prefix("Loaded image: "));
+
+                        if let Some(loaded_image) = loaded_image {
+                            println!("Loaded image '{}'", loaded_image);
+                            loaded_images.push(loaded_image.to_string());
+                        }
+                    }
+                },
+
+                _ = progress_interval.tick() => report_progress(),
+            }
+        }
+    };
+
+    let load_timeout = Duration::from_secs(app_config.image_load_timeout_secs);
+
+    match tokio::time::timeout(load_timeout, load).await {
+        Ok(load_result) => load_result?,
+
+        Err(_) => {
+            return Err(image_load_failed(format!(
+                "The load didn't finish within {} seconds",
+                load_timeout.as_secs()
+            )));
+        }
+    }
+
+    report_progress();
+
+    // Compare by registry and path, since Docker leaves out `docker.io/` in the loaded names.
+    let required_images = get_required_images(app_config)
+        .into_iter()
+        .map(|required_image| required_image.image)
+        .collect::<Vec<String>>();
+
+    let imported_images = required_images
+        .iter()
+        .filter(|required_image| {
+            loaded_images.iter().any(|loaded_image| {
+                split_image_registry(loaded_image) == split_image_registry(required_image)
+            })
+        })
+        .cloned()
+        .collect::<Vec<String>>();
+
+    if imported_images.is_empty() {
+        return Err(image_load_failed(format!(
+            "The archive didn't contain any of the images the config expects ({})",
+            required_images.join(", ")
+        )));
+    }
+
+    for imported_image in &imported_images {
+        docker.inspect_image(imported_image).await.map_err(|e| {
+            image_load_failed(format!("'{}' isn't present after the load: {}", imported_image, e))
+        })?;
+    }
+
+    Ok(imported_images)
+}
+
+/// Reads an image archive in chunks, counting the bytes read.
+///
+/// A read error ends the stream early, which Docker reports as a truncated archive.
+///
+/// # Arguments
+///
+/// * `archive` - The image archive.
+/// * `bytes_read` - The counter for the bytes read.
+fn read_archive_chunks(
+    archive: tokio::fs::File,
+    bytes_read: Arc<AtomicU64>,
+) -> impl Stream<Item = Bytes> + Send + 'static {
+    futures_util::stream::unfold((archive, bytes_read), |(mut archive, bytes_read)| async move {
+        let mut chunk = vec![0; IMAGE_LOAD_CHUNK_SIZE];
+
+        match archive.read(&mut chunk).await {
+            Ok(0) | Err(_) => None,
+
+            Ok(chunk_len) => {
+                chunk.truncate(chunk_len);
+                bytes_read.fetch_add(chunk_len as u64, Ordering::Relaxed);
+
+                Some((Bytes::from(chunk), (archive, bytes_read)))
+            }
+        }
+    })
+}
+
+/// Gets the references to pull an image through for each matching mirror.
+///
+/// Images pinned by digest aren't mirrored, since they can't be tagged with their original name.
+///
+/// # Arguments
+///
+/// * `image` - The image name and tag.
+/// * `registry_mirrors` - The configured registry mirrors.
+fn get_mirror_images(image: &str, registry_mirrors: &[RegistryMirror]) -> Vec<String> {
+    if image.contains('@') {
+        return Vec::new();
+    }
+
+    let (registry, image_path) = split_image_registry(image);
+
+    registry_mirrors
+        .iter()
+        .filter(|registry_mirror| {
+            registry_mirror.registry.as_deref().unwrap_or(DOCKER_HUB_REGISTRY) == registry
+        })
+        .map(|registry_mirror| format!("{}/{}", registry_mirror.host, image_path))
+        .collect()
+}
+
+/// Splits an image reference into its registry and the path within the registry.
+///
+/// Images without a registry are on Docker Hub, and official images
+/// (e.g. `ubuntu`) are under `library/`.
+///
+/// # Arguments
+///
+/// * `image` - The image name and tag.
+fn split_image_registry(image: &str) -> (&str, String) {
+    match image.split_once('/') {
+        Some((first_part, rest))
+            if first_part.contains('.') || first_part.contains(':') || first_part == "localhost" =>
+        {
+            let registry = match first_part {
+                "index.docker.io" | "registry-1.docker.io" => DOCKER_HUB_REGISTRY,
+                registry => registry,
+            };
+
+            match (registry, rest.contains('/')) {
+                (DOCKER_HUB_REGISTRY, false) => (registry, format!("library/{}", rest)),
+                _ => (registry, rest.to_string()),
+            }
+        }
+
+        Some(_) => (DOCKER_HUB_REGISTRY, image.to_string()),
+
+        None => (DOCKER_HUB_REGISTRY, format!("library/{}", image)),
+    }
+}
+
+/// Splits an image reference into its repository and tag.
+///
+/// The tag defaults to `latest` when one isn't set.
+///
+/// # Arguments
+///
+/// * `image` - The image name and tag.
+fn split_image_tag(image: &str) -> (&str, &str) {
+    let name_start = image.rfind('/').map(|index| index + 1).unwrap_or(0);
+
+    match image[name_start..].rfind(':') {
+        Some(tag_index) => (
+            &image[..name_start + tag_index],
+            &image[name_start + tag_index + 1..],
+        ),
+
+        None => (image, "latest"),
+    }
+}
+
+/// Pull a container image.
+///
+/// Private images are pulled with the registry's credentials from the Docker CLI config,
+/// including its credential helpers, if there are any.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `image` - The image to pull.
+/// * `requested_image` - The image to report the progress under. This differs from `image`
+///   when `image` is a registry mirror's copy of it.
+/// * `platform` - The platform to pull the image for, if any.
+async fn pull_image(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    image: &str,
+    requested_image: &str,
+    platform: Option<&str>,
+) -> Result<(), AppError> {
+    let mut create_image_opts = CreateImageOptionsBuilder::new().from_image(image);
+
+    if let Some(platform) = platform {
+        create_image_opts = create_image_opts.platform(platform);
+    }
+
+    let create_image_opts = create_image_opts.build();
+
+    let (registry, _) = split_image_registry(image);
+    let credentials = docker::get_registry_credentials(registry).await;
+
+    if credentials.is_some() {
+        println!("Pulling '{}' with the Docker credentials for '{}'", image, registry);
+    }
+
+    let mut pull_stream = docker.create_image(Some(create_image_opts), None, credentials);
+
+    let mut layers_progress = HashMap::<String, LayerProgress>::new();
+
+    // The layer the last progress message was for, used when an error doesn't say which layer failed.
+    let mut last_layer_id: Option<String> = None;
+
+    while let Some(msg) = pull_stream.next().await {
+        match msg {
+            Ok(msg) => {
+                println!("{:?}: {:?}", msg.id, msg.status);
+
+                if let Some(error_message) = msg
+                    .error_detail
+                    .as_ref()
+                    .and_then(|error_detail| error_detail.message.clone())
+                {
+                    let layer_id = msg.id.clone().or(last_layer_id);
+
+                    return Err(build_image_pull_error(image, layer_id, error_message));
+                }
+
+                if update_layers_progress(&mut layers_progress, &msg) {
+                    last_layer_id = msg.id.clone();
+                }
+
+                reporter.progress(build_pull_progress(requested_image, &msg, &layers_progress));
+            }
+
+            Err(bollard::errors::Error::DockerStreamError { error }) => {
+                return Err(build_image_pull_error(image, last_layer_id, error));
+            }
+
+            Err(err) => return Err(build_image_pull_error(image, last_layer_id, err.to_string())),
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the error for a failed image pull.
+///
+/// Pulls are done by the Docker daemon, so connectivity failures include a hint
+/// about configuring the daemon's proxy settings.
+///
+/// # Arguments
+///
+/// * `image` - The image that failed to pull.
+/// * `layer_id` - The layer that was being pulled when it failed, if known.
+/// * `err_message` - The error message from the Docker API.
+fn build_image_pull_error(image: &str, layer_id: Option<String>, err_message: String) -> AppError {
+    let err_message_lower = err_message.to_lowercase();
+
+    let is_connectivity_error = [
+        "proxy",
+        "timeout",
+        "dial tcp",
+        "connection refused",
+        "connection reset",
+        "no such host",
+        "tls handshake",
+    ]
+    .iter()
+    .any(|pattern| err_message_lower.contains(pattern));
+
+    let detail = match is_connectivity_error {
+        true => format!(
+            "{}\n\nThe Docker daemon couldn't reach the registry. If you're behind a proxy, make sure the Docker daemon is configured to use it (the 'http_proxy' and 'https_proxy' config options only apply to the containers).",
+            err_message
+        ),
+
+        false => err_message,
+    };
+
+    println!(
+        "Failed to pull '{}' (layer: {}): {}",
+        image,
+        layer_id.as_deref().unwrap_or("unknown"),
+        detail
+    );
+
+    AppError::ImagePullFailed {
+        image: image.to_string(),
+        layer: layer_id,
+        detail,
+    }
+}
+
+/// The download progress of a single image layer.
+#[derive(Debug, Clone, Default)]
+struct LayerProgress {
+    /// The number of bytes downloaded.
+    current: i64,
+
+    /// The total size of the layer, if it has been reported.
+    total: Option<i64>,
+}
+
+/// Updates the tracked layer progress from a pull stream message.
+///
+/// Returns whether the message was for a layer.
+///
+/// # Arguments
+///
+/// * `layers_progress` - The tracked progress for each layer.
+/// * `msg` - The message from the pull stream.
+fn update_layers_progress(
+    layers_progress: &mut HashMap<String, LayerProgress>,
+    msg: &CreateImageInfo,
+) -> bool {
+    let (Some(layer_id), Some(status)) = (&msg.id, &msg.status) else {
+        return false;
+    };
+
+    // The "Pulling from ..." message uses the image tag as its ID, so it isn't a layer.
+    if status.starts_with("Pulling from") {
+        return false;
+    }
+
+    let layer_progress = layers_progress.entry(layer_id.clone()).or_default();
+    let progress_detail = msg.progress_detail.as_ref();
+
+    match status.as_str() {
+        "Downloading" => {
+            if let Some(current) = progress_detail.and_then(|detail| detail.current) {
+                layer_progress.current = current;
+            }
+
+            if let Some(total) = progress_detail.and_then(|detail| detail.total) {
+                layer_progress.total = Some(total);
+            }
+        }
+
+        "Already exists" => {
+            layer_progress.current = 0;
+            layer_progress.total = Some(0);
+        }
+
+        "Verifying Checksum" | "Download complete" | "Extracting" | "Pull complete" => {
+            if layer_progress.total.is_none() && status == "Extracting" {
+                layer_progress.total = progress_detail.and_then(|detail| detail.total);
+            }
+
+            if let Some(total) = layer_progress.total {
+                layer_progress.current = total;
+            }
+        }
+
+        _ => {}
+    }
+
+    true
+}
+
+/// Builds the payload for a `pull-progress` event.
+///
+/// # Arguments
+///
+/// * `image` - The image being pulled.
+/// * `msg` - The message from the pull stream.
+/// * `layers_progress` - The tracked progress for each layer.
+fn build_pull_progress(
+    image: &str,
+    msg: &CreateImageInfo,
+    layers_progress: &HashMap<String, LayerProgress>,
+) -> PullProgress {
+    let mut downloaded_bytes = 0;
+    let mut total_bytes = 0;
+    let mut indeterminate_layers = 0;
+
+    for layer_progress in layers_progress.values() {
+        downloaded_bytes += layer_progress.current;
+
+        match layer_progress.total {
+            Some(total) => total_bytes += total,
+            None => indeterminate_layers += 1,
+        }
+    }
+
+    PullProgress {
+        image: image.to_string(),
+        layer_id: msg.id.clone(),
+        status: msg.status.clone(),
+        downloaded_bytes,
+        total_bytes,
+        indeterminate_layers,
+    }
+}
+
+/// A summary of which extra backend services started and which failed.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ExtraServicesSummary {
+    /// The names of the services that started.
+    #[serde(rename = "succeeded")]
+    pub succeeded: Vec<String>,
+
+    /// The services that failed to start.
+    #[serde(rename = "failed")]
+    pub failed: Vec<ExtraServiceFailure>,
+}
+
+/// An extra backend service that failed to start.
+#[derive(Serialize, Debug, Clone)]
+pub struct ExtraServiceFailure {
+    /// The name of the service.
+    #[serde(rename = "name")]
+    pub name: String,
+
+    /// The error that occurred.
+    #[serde(rename = "error")]
+    pub error: String,
+}
+
+impl fmt::Display for ExtraServicesSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let failures: Vec<String> = self
+            .failed
+            .iter()
+            .map(|failure| format!("'{}' ({})", failure.name, failure.error))
+            .collect();
+
+        write!(f, "{}", failures.join(", "))?;
+
+        if !self.succeeded.is_empty() {
+            write!(f, "; started successfully: {}", self.succeeded.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What happened to a network or container while setting up the infrastructure.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum CreateOutcome {
+    /// It didn't exist, so it was created.
+    #[serde(rename = "created")]
+    Created,
+
+    /// It already existed and was up to date, so it was reused.
+    #[serde(rename = "reused")]
+    Reused,
+
+    /// It already existed but was out of date, so it was removed and created again.
+    #[serde(rename = "recreated")]
+    Recreated,
+
+    /// It's no longer in the config, so it was removed.
+    #[serde(rename = "removed")]
+    Removed,
+}
+
+impl fmt::Display for CreateOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateOutcome::Created => write!(f, "created"),
+            CreateOutcome::Reused => write!(f, "reused"),
+            CreateOutcome::Recreated => write!(f, "recreated"),
+            CreateOutcome::Removed => write!(f, "removed"),
+        }
+    }
+}
+
+/// The outcome for a single network or container.
+#[derive(Serialize, Debug, Clone)]
+pub struct ResourceOutcome {
+    /// The name of the network or container.
+    #[serde(rename = "name")]
+    pub name: String,
+
+    /// Whether it's a `network` or a `container`.
+    #[serde(rename = "kind")]
+    pub kind: String,
+
+    /// What happened to it.
+    #[serde(rename = "outcome")]
+    pub outcome: CreateOutcome,
+}
+
+/// A summary of what was created, reused, or recreated while setting up the infrastructure.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct InfrastructureSummary {
+    /// The outcome for each network and container.
+    #[serde(rename = "resources")]
+    pub resources: Vec<ResourceOutcome>,
+
+    /// Problems that didn't stop the infrastructure from being set up, like Tika failing to start.
+    #[serde(rename = "warnings")]
+    pub warnings: Vec<String>,
+}
+
+impl InfrastructureSummary {
+    /// Adds the outcome for a network.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the network.
+    /// * `outcome` - What happened to it.
+    fn add_network(&mut self, name: &str, outcome: CreateOutcome) {
+        self.resources.push(ResourceOutcome {
+            name: name.to_string(),
+            kind: "network".to_string(),
+            outcome,
+        });
+    }
+
+    /// Adds the outcome for a container.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the container.
+    /// * `outcome` - What happened to it.
+    fn add_container(&mut self, name: &str, outcome: CreateOutcome) {
+        self.resources.push(ResourceOutcome {
+            name: name.to_string(),
+            kind: "container".to_string(),
+            outcome,
+        });
+    }
+
+    /// Adds a problem that didn't stop the infrastructure from being set up.
+    ///
+    /// # Arguments
+    ///
+    /// * `warning` - The warning message.
+    fn add_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+}
+
+impl fmt::Display for InfrastructureSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut groups = Vec::new();
+
+        for outcome in [
+            CreateOutcome::Created,
+            CreateOutcome::Reused,
+            CreateOutcome::Recreated,
+            CreateOutcome::Removed,
+        ] {
+            let names: Vec<&str> = self
+                .resources
+                .iter()
+                .filter(|resource| resource.outcome == outcome)
+                .map(|resource| resource.name.as_str())
+                .collect();
+
+            if !names.is_empty() {
+                groups.push(format!("{}: {}", outcome, names.join(", ")));
+            }
+        }
+
+        write!(f, "{}", groups.join("; "))
+    }
+}
+
+/// Creates the infrastructure needed to run the application.
+///
+/// This includes:
+///
+/// * The frontend and backend networks.
+/// * Running Open WebUI.
+/// * Running any backend services needed for Open WebUI.
+///   * For example, Apache Tika.
+///
+/// If any extra backend service fails to start, the extra service containers
+/// created in this run are removed and an error naming the failed services is returned.
+///
+/// If anything fails, only the networks and containers created in this run are removed,
+/// so networks that were reused are left alone.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The host path to data directory to mount into the Open WebUI container.
+/// * `host_port` - The port on the host to bind Open WebUI to.
+pub async fn create_infrastructure(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+    host_port: u16,
+) -> Result<InfrastructureSummary, AppError> {
+    let mut infrastructure_summary = InfrastructureSummary::default();
+
+    match create_infrastructure_resources(
+        docker,
+        app_config,
+        data_dir,
+        host_port,
+        &mut infrastructure_summary,
+    )
+    .await
+    {
+        Ok(_) => Ok(infrastructure_summary),
+
+        Err(err) => {
+            remove_created_resources(docker, &infrastructure_summary).await;
+
+            Err(err)
+        }
+    }
+}
+
+/// Creates the networks and containers, adding each one to the summary as it's created.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The host path to data directory to mount into the Open WebUI container.
+/// * `host_port` - The port on the host to bind Open WebUI to.
+/// * `infrastructure_summary` - The summary to add the created networks and containers to.
+async fn create_infrastructure_resources(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+    host_port: u16,
+    infrastructure_summary: &mut InfrastructureSummary,
+) -> Result<(), AppError> {
+    let frontend_network_outcome = create_frontend_network(docker, app_config).await?;
+    infrastructure_summary.add_network("local_llm_frontend", frontend_network_outcome);
+
+    let backend_network_outcome = create_backend_network(docker, app_config).await?;
+    infrastructure_summary.add_network("local_llm_backend", backend_network_outcome);
+
+    create_openwebui_container(docker, app_config, data_dir, host_port).await?;
+    infrastructure_summary.add_container("local_llm_openwebui", CreateOutcome::Created);
+
+    if let Some(reverse_proxy) = &app_config.reverse_proxy {
+        create_reverse_proxy_container(docker, reverse_proxy, host_port).await?;
+        infrastructure_summary.add_container(&get_container_name(REVERSE_PROXY_SERVICE_NAME), CreateOutcome::Created);
+    }
+
+    match create_tika_container(docker, app_config).await {
+        Ok(_) => infrastructure_summary.add_container("local_llm_tika", CreateOutcome::Created),
+
+        Err(err) => handle_tika_failure(docker, app_config, infrastructure_summary, err).await?,
+    }
+
+    if let Some(embeddings_backend) = &app_config.embeddings_backend {
+        create_embeddings_backend_container(docker, embeddings_backend).await?;
+        infrastructure_summary.add_container(
+            &get_container_name(EMBEDDINGS_BACKEND_SERVICE_NAME),
+            CreateOutcome::Created,
+        );
+    }
+
+    let mut extra_services_summary = ExtraServicesSummary::default();
+
+    let extra_services = app_config.get_enabled_extra_services();
+    let mut extra_services_tasks = Vec::with_capacity(extra_services.len());
+
+    for extra_service in extra_services {
+        extra_services_tasks.push((
+            extra_service.name.clone(),
+            tokio::spawn(create_extra_service_container(
+                docker.clone(),
+                extra_service,
+                app_config.get_proxy_env(),
+            )),
+        ));
+    }
+
+    for (service_name, task) in extra_services_tasks {
+        let task_result = task.await.unwrap_or_else(|e| {
+            Err(AppError::GenericError(format!(
+                "The task for creating the service failed: {}",
+                e
+            )))
+        });
+
+        match task_result {
+            Ok(_) => extra_services_summary.succeeded.push(service_name),
+
+            Err(err) => extra_services_summary.failed.push(ExtraServiceFailure {
+                name: service_name,
+                error: err.to_string(),
+            }),
+        }
+    }
+
+    if !extra_services_summary.failed.is_empty() {
+        // Only remove the extra service containers created in this run.
+        // The failed services are included, since they may have been created but failed to start.
+        let service_names = extra_services_summary
+            .succeeded
+            .iter()
+            .chain(extra_services_summary.failed.iter().map(|failure| &failure.name));
+
+        for service_name in service_names {
+            ensure_container_removed(docker, &get_container_name(service_name)).await?;
+        }
+
+        return Err(AppError::ExtraServicesFailed(extra_services_summary));
+    }
+
+    for service_name in &extra_services_summary.succeeded {
+        infrastructure_summary
+            .add_container(&get_container_name(service_name), CreateOutcome::Created);
+    }
+
+    Ok(())
+}
+
+/// Removes the networks and containers that were created, after setting up the
+/// infrastructure failed.
+///
+/// Containers are removed before networks, in the reverse order they were created.
+/// Failures are logged rather than returned, so the error that caused the rollback
+/// is the one reported.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `infrastructure_summary` - The summary of what was created before the failure.
+async fn remove_created_resources(docker: &Docker, infrastructure_summary: &InfrastructureSummary) {
+    let created_resources = infrastructure_summary
+        .resources
+        .iter()
+        .rev()
+        .filter(|resource| resource.outcome == CreateOutcome::Created);
+
+    for resource in created_resources.clone().filter(|resource| resource.kind == "container") {
+        if let Err(err) = ensure_container_removed(docker, &resource.name).await {
+            println!(
+                "Warning: Failed to remove container '{}' after setup failed: {}",
+                resource.name, err
+            );
+        }
+    }
+
+    for resource in created_resources.filter(|resource| resource.kind == "network") {
+        match docker.remove_network(&resource.name).await {
+            Ok(_) => println!("Removed network '{}'", resource.name),
+
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => {}
+
+            Err(err) => println!(
+                "Warning: Failed to remove network '{}' after setup failed: {}",
+                resource.name, err
+            ),
+        }
+    }
+}
+
+/// Brings the existing Docker resources in line with the config, without a full teardown.
+///
+/// This is used instead of cleaning up and recreating everything when
+/// `cleanup_on_exit` is disabled, or when managed containers were left behind by a
+/// previous session, so those containers can be adopted:
+///
+/// * Missing networks and containers are created.
+/// * Containers that are stopped, or don't match the config's image, host ports,
+///   or bind mounts, are recreated.
+/// * Managed containers that are no longer in the config are removed.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The host path to data directory to mount into the Open WebUI container.
+/// * `host_port` - The port on the host to bind Open WebUI to.
+pub async fn reconcile_infrastructure(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+    host_port: u16,
+) -> Result<InfrastructureSummary, AppError> {
+    let mut infrastructure_summary = InfrastructureSummary::default();
+
+    let frontend_network_outcome = create_frontend_network(docker, app_config).await?;
+    infrastructure_summary.add_network("local_llm_frontend", frontend_network_outcome);
+
+    let backend_network_outcome = create_backend_network(docker, app_config).await?;
+    infrastructure_summary.add_network("local_llm_backend", backend_network_outcome);
+
+    // The host port moves between Open WebUI and the reverse proxy when the proxy is
+    // added or removed, so Open WebUI is recreated to release or take the port.
+    let openwebui_host_ports = match app_config.reverse_proxy {
+        Some(_) => vec![],
+        None => vec![host_port],
+    };
+
+    let outcome = reconcile_container(
+        docker,
+        "local_llm_openwebui",
+        &ExpectedContainer {
+            image: get_openwebui_image(app_config),
+            binds: get_openwebui_binds(app_config, data_dir),
+            host_ports: openwebui_host_ports,
+            env: get_openwebui_env(app_config, host_port),
+            labels: build_container_labels(&app_config.labels),
+        },
+        create_openwebui_container(docker, app_config, data_dir, host_port),
+    )
+    .await?;
+    infrastructure_summary.add_container("local_llm_openwebui", outcome);
+
+    if let Some(reverse_proxy) = &app_config.reverse_proxy {
+        let container_name = get_container_name(REVERSE_PROXY_SERVICE_NAME);
+
+        let outcome = reconcile_container(
+            docker,
+            &container_name,
+            &ExpectedContainer {
+                image: reverse_proxy.image.clone(),
+                binds: get_volume_binds(&reverse_proxy.volume_bindings).unwrap_or_default(),
+                host_ports: vec![host_port],
+                env: get_reverse_proxy_env(reverse_proxy),
+                labels: build_container_labels(&reverse_proxy.labels),
+            },
+            create_reverse_proxy_container(docker, reverse_proxy, host_port),
+        )
+        .await?;
+        infrastructure_summary.add_container(&container_name, outcome);
+    }
+
+    let tika_outcome = reconcile_container(
+        docker,
+        "local_llm_tika",
+        &ExpectedContainer {
+            image: get_tika_image(app_config),
+            binds: vec![],
+            host_ports: app_config.tika_host_port.into_iter().collect(),
+            env: vec![],
+            labels: build_container_labels(&app_config.labels),
+        },
+        create_tika_container(docker, app_config),
+    )
+    .await;
+
+    match tika_outcome {
+        Ok(outcome) => infrastructure_summary.add_container("local_llm_tika", outcome),
+
+        Err(err) => {
+            handle_tika_failure(docker, app_config, &mut infrastructure_summary, err).await?
+        }
+    }
+
+    if let Some(embeddings_backend) = &app_config.embeddings_backend {
+        let container_name = get_container_name(EMBEDDINGS_BACKEND_SERVICE_NAME);
+
+        let outcome = reconcile_container(
+            docker,
+            &container_name,
+            &ExpectedContainer {
+                image: embeddings_backend.image.clone(),
+                binds: vec![],
+                host_ports: vec![],
+                env: embeddings_backend.env.clone().unwrap_or_default(),
+                labels: build_container_labels(&None),
+            },
+            create_embeddings_backend_container(docker, embeddings_backend),
+        )
+        .await?;
+        infrastructure_summary.add_container(&container_name, outcome);
+    }
+
+    for extra_service in app_config.get_enabled_extra_services() {
+        let container_name = get_container_name(&extra_service.name);
+        let expected_container = ExpectedContainer {
+            image: extra_service.image.clone(),
+            binds: get_volume_binds(&extra_service.volume_bindings).unwrap_or_default(),
+            host_ports: vec![],
+            env: get_extra_service_env(&extra_service, &app_config.get_proxy_env())?
+                .unwrap_or_default(),
+            labels: build_container_labels(&extra_service.labels),
+        };
+
+        let outcome = reconcile_container(
+            docker,
+            &container_name,
+            &expected_container,
+            create_extra_service_container(
+                docker.clone(),
+                extra_service,
+                app_config.get_proxy_env(),
+            ),
+        )
+        .await?;
+        infrastructure_summary.add_container(&container_name, outcome);
+    }
+
+    // Remove any managed containers that are no longer in the config.
+    let expected_container_names = get_managed_resources(app_config).containers;
+
+    for container_status in get_container_statuses(docker).await? {
+        if !expected_container_names.contains(&container_status.name) {
+            ensure_container_removed(docker, &container_status.name).await?;
+            infrastructure_summary.add_container(&container_status.name, CreateOutcome::Removed);
+        }
+    }
+
+    Ok(infrastructure_summary)
+}
+
+/// Removes and recreates a single service's container with the given config.
+///
+/// The name is either `openwebui`, `tika`, `reverse_proxy`, `embeddings`, or the name of
+/// an extra backend service.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The host path to data directory to mount into the Open WebUI container.
+/// * `host_port` - The port on the host to bind Open WebUI to.
+/// * `service_name` - The name of the service to recreate.
+pub async fn recreate_service(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+    host_port: u16,
+    service_name: &str,
+) -> Result<(), AppError> {
+    let container_name = get_container_name(service_name);
+
+    match service_name {
+        "openwebui" => {
+            ensure_container_removed(docker, &container_name).await?;
+            create_openwebui_container(docker, app_config, data_dir, host_port).await?;
+        }
+
+        "tika" => {
+            ensure_container_removed(docker, &container_name).await?;
+            create_tika_container(docker, app_config).await?;
+        }
+
+        REVERSE_PROXY_SERVICE_NAME if app_config.reverse_proxy.is_some() => {
+            let reverse_proxy = app_config.reverse_proxy.as_ref().unwrap();
+
+            ensure_container_removed(docker, &container_name).await?;
+            create_reverse_proxy_container(docker, reverse_proxy, host_port).await?;
+        }
+
+        EMBEDDINGS_BACKEND_SERVICE_NAME if app_config.embeddings_backend.is_some() => {
+            let embeddings_backend = app_config.embeddings_backend.as_ref().unwrap();
+
+            ensure_container_removed(docker, &container_name).await?;
+            create_embeddings_backend_container(docker, embeddings_backend).await?;
+        }
+
+        _ => {
+            let extra_service = app_config
+                .extra_backend_services
+                .iter()
+                .flatten()
+                .find(|extra_service| extra_service.name == service_name)
+                .cloned()
+                .ok_or_else(|| {
+                    AppError::ConfigError(format!(
+                        "'{}' isn't a service in the current config",
+                        service_name
+                    ))
+                })?;
+
+            if !extra_service.enabled {
+                return Err(AppError::ConfigError(format!(
+                    "'{}' is disabled in the current config",
+                    service_name
+                )));
+            }
+
+            ensure_container_removed(docker, &container_name).await?;
+            create_extra_service_container(docker.clone(), extra_service, app_config.get_proxy_env())
+                .await?;
+        }
+    }
+
+    println!("Recreated container '{}'", container_name);
+
+    Ok(())
+}
+
+/// The state of an existing container compared to what's expected.
+enum ExistingContainer {
+    /// The container doesn't exist.
+    Missing,
+
+    /// The container is running and matches what's expected.
+    Current,
+
+    /// The container exists, but is stopped or doesn't match what's expected.
+    Stale,
+}
+
+/// What an existing container has to match to be reused.
+struct ExpectedContainer {
+    /// The image the container should be using.
+    image: String,
+
+    /// The bind mounts the container should have, in the `host:container[:options]` format.
+    binds: Vec<String>,
+
+    /// The ports the container should have bound on the host.
+    host_ports: Vec<u16>,
+
+    /// The environment variables the container should have, in the `KEY=value` format.
+    ///
+    /// The container can have other variables too, since the image's variables are merged in.
+    env: Vec<String>,
+
+    /// The labels the container should have.
+    ///
+    /// The container can have other labels too, since the image's labels are merged in.
+    labels: HashMap<String, String>,
+}
+
+/// Reuses a container if it's current, otherwise (re)creates it.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the container.
+/// * `expected_container` - What the container has to match to be reused.
+/// * `create_container` - Creates and starts the container. This is only awaited if the container isn't current.
+async fn reconcile_container(
+    docker: &Docker,
+    container_name: &str,
+    expected_container: &ExpectedContainer,
+    create_container: impl Future<Output = Result<(), AppError>>,
+) -> Result<CreateOutcome, AppError> {
+    match get_existing_container(docker, container_name, expected_container).await? {
+        ExistingContainer::Current => Ok(CreateOutcome::Reused),
+
+        ExistingContainer::Missing => {
+            println!("Creating container '{}'", container_name);
+            create_container.await?;
+
+            Ok(CreateOutcome::Created)
+        }
+
+        ExistingContainer::Stale => {
+            println!("Recreating container '{}'", container_name);
+            ensure_container_removed(docker, container_name).await?;
+            create_container.await?;
+
+            Ok(CreateOutcome::Recreated)
+        }
+    }
+}
+
+/// Creates a network managed by the app, or reuses it if it already exists.
+///
+/// Networks are kept between launches, so the network isn't recreated every time.
+/// An existing network is only recreated if it wasn't created by the app or its driver
+/// or options don't match the request. If it can't be removed because containers outside
+/// of the app are still attached to it, it's reused as-is.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `network_request` - The network to create. The management label is added to it.
+async fn create_or_reuse_network(
+    docker: &Docker,
+    mut network_request: NetworkCreateRequest,
+) -> Result<CreateOutcome, AppError> {
+    let network_name = network_request.name.clone();
+    let expected_driver = network_request.driver.as_deref().unwrap_or("bridge");
+
+    let existing_network = match docker
+        .inspect_network(&network_name, None::<InspectNetworkOptions>)
+        .await
+    {
+        Ok(network) => Some(network),
+
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => None,
+
+        Err(err) => return Err(docker::map_docker_error(docker, err)),
+    };
+
+    let is_recreated = match existing_network {
+        Some(network) => {
+            let is_managed = network
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.get(MANAGED_LABEL_KEY))
+                .is_some_and(|value| value == "true");
+
+            let is_same_driver = network.driver.as_deref() == Some(expected_driver);
+
+            let network_options = network.options.unwrap_or_default();
+            let expected_options = network_request.options.clone().unwrap_or_default();
+
+            // An MTU that was removed from the config has to be removed from the network too.
+            let is_same_options = expected_options
+                .iter()
+                .all(|(key, value)| network_options.get(key) == Some(value))
+                && (expected_options.contains_key(NETWORK_MTU_DRIVER_OPTION)
+                    || !network_options.contains_key(NETWORK_MTU_DRIVER_OPTION));
+
+            if is_managed && is_same_driver && is_same_options {
+                return Ok(CreateOutcome::Reused);
+            }
+
+            println!(
+                "Network '{}' wasn't created by the app or doesn't match its driver or options",
+                network_name
+            );
+
+            match docker.remove_network(&network_name).await {
+                Ok(_) => println!("Removed network '{}'", network_name),
+
+                // Containers outside of the app are still attached to the network.
+                Err(bollard::errors::Error::DockerResponseServerError {
+                    status_code: 403, ..
+                }) => {
+                    println!(
+                        "Warning: Network '{}' is in use by other containers, so it was reused as-is",
+                        network_name
+                    );
+
+                    return Ok(CreateOutcome::Reused);
+                }
+
+                Err(err) => return Err(docker::map_docker_error(docker, err)),
+            }
+
+            true
+        }
+
+        None => false,
+    };
+
+    network_request.labels = Some(build_container_labels(&network_request.labels));
+
+    docker
+        .create_network(network_request)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    match is_recreated {
+        true => Ok(CreateOutcome::Recreated),
+        false => Ok(CreateOutcome::Created),
+    }
+}
+
+/// Checks if a container has all of the expected environment variables, with the same values.
+///
+/// # Arguments
+///
+/// * `container_env` - The container's environment variables.
+/// * `expected_env` - The environment variables the container should have.
+fn has_expected_env(container_env: &[String], expected_env: &[String]) -> bool {
+    let split_env_var = |env_var: &String| match env_var.split_once('=') {
+        Some((key, value)) => (key.to_string(), value.to_string()),
+        None => (env_var.clone(), String::new()),
+    };
+
+    // Later variables take precedence, the same as when Docker creates the container.
+    let container_env: HashMap<String, String> = container_env.iter().map(split_env_var).collect();
+    let expected_env: HashMap<String, String> = expected_env.iter().map(split_env_var).collect();
+
+    expected_env
+        .iter()
+        .all(|(key, value)| container_env.get(key) == Some(value))
+}
+
+/// Checks if a container exists, is running, and matches what's expected.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the container.
+/// * `expected_container` - What the container has to match.
+async fn get_existing_container(
+    docker: &Docker,
+    container_name: &str,
+    expected_container: &ExpectedContainer,
+) -> Result<ExistingContainer, AppError> {
+    match docker
+        .inspect_container(container_name, None::<InspectContainerOptions>)
+        .await
+    {
+        Ok(container) => {
+            let is_running = container
+                .state
+                .as_ref()
+                .and_then(|state| state.running)
+                .unwrap_or(false);
+
+            let is_same_image = is_same_image(&container, &expected_container.image);
+            let is_same_config = is_same_config(&container, expected_container);
+
+            if is_running && is_same_image && !is_same_config {
+                println!(
+                    "Container '{}' doesn't match the config's host ports, mounts, environment, or labels",
+                    container_name
+                );
+            }
+
+            match is_running && is_same_image && is_same_config {
+                true => Ok(ExistingContainer::Current),
+                false => Ok(ExistingContainer::Stale),
+            }
+        }
+
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(ExistingContainer::Missing),
+
+        Err(err) => Err(docker::map_docker_error(docker, err)),
+    }
+}
+
+/// Checks if a container was created from the expected image.
+///
+/// # Arguments
+///
+/// * `container` - The inspected container.
+/// * `expected_image` - The image the container should be using.
+fn is_same_image(container: &ContainerInspectResponse, expected_image: &str) -> bool {
+    container
+        .config
+        .as_ref()
+        .and_then(|config| config.image.as_deref())
+        == Some(expected_image)
+}
+
+/// Checks if a container's mounts, host ports, environment, and labels match what's expected.
+///
+/// # Arguments
+///
+/// * `container` - The inspected container.
+/// * `expected_container` - What the container has to match to be reused.
+fn is_same_config(
+    container: &ContainerInspectResponse,
+    expected_container: &ExpectedContainer,
+) -> bool {
+    let mut binds = container
+        .host_config
+        .as_ref()
+        .and_then(|host_config| host_config.binds.clone())
+        .unwrap_or_default();
+    binds.sort();
+
+    let mut expected_binds = expected_container.binds.clone();
+    expected_binds.sort();
+
+    let mut host_ports = get_bound_host_ports(container);
+    host_ports.sort();
+
+    let mut expected_host_ports = expected_container.host_ports.clone();
+    expected_host_ports.sort();
+
+    let container_env = container
+        .config
+        .as_ref()
+        .and_then(|config| config.env.clone())
+        .unwrap_or_default();
+
+    let container_labels = container
+        .config
+        .as_ref()
+        .and_then(|config| config.labels.clone())
+        .unwrap_or_default();
+
+    binds == expected_binds
+        && host_ports == expected_host_ports
+        && has_expected_env(&container_env, &expected_container.env)
+        && has_expected_labels(&container_labels, &expected_container.labels)
+}
+
+/// Checks if a container has all of the expected labels, with the same values.
+///
+/// # Arguments
+///
+/// * `container_labels` - The container's labels.
+/// * `expected_labels` - The labels the container should have.
+fn has_expected_labels(
+    container_labels: &HashMap<String, String>,
+    expected_labels: &HashMap<String, String>,
+) -> bool {
+    expected_labels
+        .iter()
+        .all(|(key, value)| container_labels.get(key) == Some(value))
+}
+
+/// Gets the ports a container has bound on the host.
+///
+/// # Arguments
+///
+/// * `container` - The inspected container.
+fn get_bound_host_ports(container: &ContainerInspectResponse) -> Vec<u16> {
+    container
+        .host_config
+        .as_ref()
+        .and_then(|host_config| host_config.port_bindings.as_ref())
+        .into_iter()
+        .flat_map(|port_bindings| port_bindings.values())
+        .flatten()
+        .flatten()
+        .filter_map(|port_binding| port_binding.host_port.as_deref())
+        .filter_map(|host_port| host_port.parse::<u16>().ok())
+        .collect()
+}
+
+/// The driver option for setting a network's MTU.
+const NETWORK_MTU_DRIVER_OPTION: &'static str = "com.docker.network.driver.mtu";
+
+/// Creates the `local_llm_frontend` network with Docker (or any Docker-compatible API).
+///
+/// The network is reused if it already exists with the same settings.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+async fn create_frontend_network(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+) -> Result<CreateOutcome, AppError> {
+    let mut driver_opts = HashMap::<String, String>::new();
+    driver_opts.insert(
+        "com.docker.network.bridge.host_binding_ipv4".to_string(),
+        "127.0.0.1".to_string(),
+    );
+
+    if let Some(network_mtu) = app_config.network_mtu {
+        driver_opts.insert(NETWORK_MTU_DRIVER_OPTION.to_string(), network_mtu.to_string());
+    }
+
+    create_or_reuse_network(
+        docker,
+        NetworkCreateRequest {
+            name: "local_llm_frontend".to_string(),
+            driver: Some("bridge".to_string()),
+            options: Some(driver_opts),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Creates the `local_llm_backend` network with Docker (or any Docker-compatible API).
+///
+/// The network is reused if it already exists with the same settings.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+async fn create_backend_network(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+) -> Result<CreateOutcome, AppError> {
+    let driver_opts = app_config.network_mtu.map(|network_mtu| {
+        HashMap::from([(NETWORK_MTU_DRIVER_OPTION.to_string(), network_mtu.to_string())])
+    });
+
+    create_or_reuse_network(
+        docker,
+        NetworkCreateRequest {
+            name: "local_llm_backend".to_string(),
+            options: driver_opts,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Checks that no two mounts for a container use the same container path.
+///
+/// Docker either fails to create the container with a cryptic error or, for some
+/// runtimes, silently shadows one of the mounts. Mounts nested inside another mount's
+/// path are allowed, since Docker mounts the outer path first.
+///
+/// # Arguments
+///
+/// * `container_name` - The name of the container, for the error message.
+/// * `mounts` - The host path and container path of each mount.
+fn check_mount_collisions(container_name: &str, mounts: &[(&str, &str)]) -> Result<(), AppError> {
+    for (index, (host_path, container_path)) in mounts.iter().enumerate() {
+        let normalized_path = normalize_container_path(container_path);
+
+        let collision = mounts[..index].iter().find(|(_, other_container_path)| {
+            normalize_container_path(other_container_path) == normalized_path
+        });
+
+        if let Some((other_host_path, other_container_path)) = collision {
+            return Err(AppError::ConfigError(format!(
+                "The mounts '{}:{}' and '{}:{}' for '{}' use the same container path",
+                other_host_path, other_container_path, host_path, container_path, container_name
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Gets the bind mounts for the Open WebUI container.
+///
+/// The data directory is mounted first, followed by the `data_sub_mounts` over it.
+/// The mount options apply to all of them.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The host path to the data directory.
+fn get_openwebui_binds(app_config: &LlmChatConfig, data_dir: &PathBuf) -> Vec<String> {
+    let format_bind = |host_path: &str, container_path: &str| match &app_config.data_mount_options {
+        Some(data_mount_options) => format!(
+            "{}:{}:{}",
+            host_path,
+            container_path,
+            data_mount_options.replace(' ', "")
+        ),
+
+        None => format!("{}:{}", host_path, container_path),
+    };
+
+    let mut binds = vec![format_bind(&data_dir.to_string_lossy(), OPEN_WEBUI_DATA_PATH)];
+
+    for (host_path, container_path) in get_openwebui_sub_mounts(app_config) {
+        binds.push(format_bind(&host_path, &container_path));
+    }
+
+    binds
+}
+
+/// Gets the host path and container path of each of the `data_sub_mounts`.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+fn get_openwebui_sub_mounts(app_config: &LlmChatConfig) -> Vec<(String, String)> {
+    app_config
+        .data_sub_mounts
+        .iter()
+        .flatten()
+        .map(|data_sub_mount| {
+            (
+                data_sub_mount.host_path.clone(),
+                format!("{}/{}", OPEN_WEBUI_DATA_PATH, data_sub_mount.get_subpath()),
+            )
+        })
+        .collect()
+}
+
+/// Gets the bind mounts for the volume bindings in the config, if any.
+///
+/// # Arguments
+///
+/// * `volume_bindings` - The volume bindings from the config, if any.
+fn get_volume_binds(
+    volume_bindings: &Option<Vec<BackendServiceHostVolumePathBinding>>,
+) -> Option<Vec<String>> {
+    volume_bindings.as_ref().map(|volume_bindings| {
+        volume_bindings
+            .iter()
+            .map(|volume| {
+                format!(
+                    "{host_path}:{container_path}",
+                    host_path = volume.host_path,
+                    container_path = volume.container_path
+                )
+            })
+            .collect::<Vec<String>>()
+    })
+}
+
+/// Gets the host path and container path of each volume binding.
+///
+/// # Arguments
+///
+/// * `volume_bindings` - The volume bindings from the config, if any.
+fn get_volume_binding_mounts(
+    volume_bindings: &Option<Vec<BackendServiceHostVolumePathBinding>>,
+) -> Vec<(&str, &str)> {
+    volume_bindings
+        .iter()
+        .flatten()
+        .map(|volume_binding| {
+            (
+                volume_binding.host_path.as_str(),
+                volume_binding.container_path.as_str(),
+            )
+        })
+        .collect()
+}
+
+/// Gets the container path and options of each tmpfs mount in the config, if any.
+///
+/// # Arguments
+///
+/// * `tmpfs` - The tmpfs mounts from the config, in the `path[:options]` form.
+fn get_tmpfs_mounts(tmpfs: &Option<Vec<String>>) -> Option<Vec<(String, String)>> {
+    tmpfs.as_ref().map(|tmpfs| {
+        tmpfs
+            .iter()
+            .map(|tmpfs_mount| match tmpfs_mount.split_once(':') {
+                Some((container_path, options)) => {
+                    (container_path.to_string(), options.to_string())
+                }
+
+                None => (tmpfs_mount.clone(), String::new()),
+            })
+            .collect::<Vec<(String, String)>>()
+    })
+}
+
+/// Normalizes a container path for comparison, removing empty and `.` components.
+///
+/// # Arguments
+///
+/// * `container_path` - The container path.
+fn normalize_container_path(container_path: &str) -> String {
+    let components = container_path
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<&str>>();
+
+    format!("/{}", components.join("/"))
+}
+
+/// Builds the device requests for giving a container access to GPUs.
+///
+/// # Arguments
+///
+/// * `gpu` - The GPU config.
+fn build_device_requests(gpu: &GpuConfig) -> Vec<DeviceRequest> {
+    // Use all GPUs when neither a count nor specific devices are set.
+    let count = match (&gpu.count, &gpu.device_ids) {
+        (None, None) => Some(-1),
+        (count, _) => *count,
+    };
+
+    let capabilities = gpu
+        .capabilities
+        .clone()
+        .unwrap_or_else(|| vec!["gpu".to_string()]);
+
+    vec![DeviceRequest {
+        driver: gpu.driver.clone(),
+        count,
+        device_ids: gpu.device_ids.clone(),
+        capabilities: Some(vec![capabilities]),
+        ..Default::default()
+    }]
+}
+
+/// The extra host that resolves `host.docker.internal` to the host.
+const HOST_GATEWAY_EXTRA_HOST: &'static str = "host.docker.internal:host-gateway";
+
+/// Gets the extra hosts for the Open WebUI container.
+///
+/// Docker Desktop resolves `host.docker.internal` to the host already, but Docker on Linux
+/// doesn't, so it's added when an external Ollama is used.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+fn get_openwebui_extra_hosts(app_config: &LlmChatConfig) -> Option<Vec<String>> {
+    match cfg!(target_os = "linux") && app_config.external_ollama_url.is_some() {
+        true => Some(vec![HOST_GATEWAY_EXTRA_HOST.to_string()]),
+        false => None,
+    }
+}
+
+/// Gets the environment variables for the Open WebUI container.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+/// * `host_port` - The port on the host that Open WebUI is reached on.
+fn get_openwebui_env(app_config: &LlmChatConfig, host_port: u16) -> Vec<String> {
+    // Signing in with OpenID Connect needs Open WebUI's authentication enabled.
+    let webui_auth = app_config.oidc.is_some();
+
+    let mut container_env = vec![
+        "ENV=dev".to_string(),
+        format!("WEBUI_AUTH={}", webui_auth),
+        //"WEB_LOADER_ENGINE=playwright".to_string(),
+        //"PLAYWRIGHT_WS_URI=ws://playwright:3000".to_string(),
+    ];
+
+    container_env.extend(app_config.get_proxy_env());
+
+    if let Some(oidc) = &app_config.oidc {
+        container_env.extend(oidc.get_env());
+    }
+
+    if let Some(external_ollama_url) = &app_config.external_ollama_url {
+        container_env.push(format!("OLLAMA_BASE_URL={}", external_ollama_url));
+    }
+
+    if let Some(embeddings_backend) = &app_config.embeddings_backend {
+        container_env.extend(embeddings_backend.get_openwebui_env());
+    }
+
+    // The provider redirects back to `WEBUI_URL` after signing in.
+    if app_config.base_path.is_some() || app_config.oidc.is_some() {
+        container_env.push(format!(
+            "WEBUI_URL=http://localhost:{}{}",
+            host_port,
+            app_config.get_base_path()
+        ));
+    }
+
+    container_env
+}
+
+/// Creates and starts the Open WebUI container with Docker (or any Docker-compatible API).
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `data_dir` - The host path to data directory to mount into the container.
+/// * `host_port` - The port on the host to bind Open WebUI to.
+///
+/// # Notes
+///
+/// The name of the container will always be `local_llm_openwebui`.
+async fn create_openwebui_container(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+    host_port: u16,
+) -> Result<(), AppError> {
+    let open_webui_image = get_openwebui_image(app_config);
+
+    let mut create_container_opts = CreateContainerOptionsBuilder::new().name("local_llm_openwebui");
+
+    if let Some(platform) = &app_config.platform {
+        create_container_opts = create_container_opts.platform(platform);
+    }
+
+    let create_container_opts = create_container_opts.build();
+
+    let container_env = get_openwebui_env(app_config, host_port);
+
+    let data_dir_path = data_dir.to_string_lossy();
+    let sub_mounts = get_openwebui_sub_mounts(app_config);
+
+    let mut mounts = vec![(data_dir_path.as_ref(), OPEN_WEBUI_DATA_PATH)];
+    mounts.extend(
+        sub_mounts
+            .iter()
+            .map(|(host_path, container_path)| (host_path.as_str(), container_path.as_str())),
+    );
+
+    check_mount_collisions("local_llm_openwebui", &mounts)?;
+
+    let mut networks = HashMap::<String, EndpointSettings>::new();
+    networks.insert(
+        "local_llm_frontend".to_string(),
+        EndpointSettings::default(),
+    );
+    networks.insert("local_llm_backend".to_string(), EndpointSettings::default());
+
+    let networking_config = NetworkingConfig {
+        endpoints_config: Some(networks),
+    };
+
+    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
+    container_ports.insert("8080/tcp".to_string(), HashMap::default());
+
+    // When there's a reverse proxy, the proxy is bound to the host port instead.
+    let port_binds = match app_config.reverse_proxy {
+        Some(_) => None,
+
+        None => {
+            let mut port_binds = HashMap::<String, Option<Vec<PortBinding>>>::new();
+            port_binds.insert(
+                "8080/tcp".to_string(),
+                Some(vec![PortBinding {
+                    host_port: Some(host_port.to_string()),
+                    ..Default::default()
+                }]),
+            );
+
+            Some(port_binds)
+        }
+    };
+
+    let host_config = HostConfig {
+        binds: Some(get_openwebui_binds(app_config, &data_dir)),
+        port_bindings: port_binds,
+        device_requests: app_config.gpu.as_ref().map(build_device_requests),
+        extra_hosts: get_openwebui_extra_hosts(app_config),
+        ..Default::default()
+    };
+
+    let container_config = ContainerCreateBody {
+        image: Some(open_webui_image),
+        env: Some(container_env),
+        networking_config: Some(networking_config),
+        exposed_ports: Some(container_ports),
+        host_config: Some(host_config),
+        labels: Some(build_container_labels(&app_config.labels)),
+        stop_timeout: Some(app_config.openwebui_stop_timeout_secs as i64),
+        ..Default::default()
+    };
+
+    docker
+        .create_container(Some(create_container_opts), container_config)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
+
+    docker
+        .start_container("local_llm_openwebui", Some(start_container_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    Ok(())
+}
+
+/// Gets the environment variables for the reverse proxy container.
+///
+/// # Arguments
+///
+/// * `reverse_proxy` - The reverse proxy config.
+fn get_reverse_proxy_env(reverse_proxy: &ReverseProxyConfig) -> Vec<String> {
+    let mut container_env = vec!["LOCALLM_UPSTREAM=local_llm_openwebui:8080".to_string()];
+    container_env.extend(reverse_proxy.env.iter().flatten().cloned());
+
+    container_env
+}
+
+/// Creates and starts the reverse proxy container with Docker (or any Docker-compatible API).
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `reverse_proxy` - The reverse proxy config.
+/// * `host_port` - The port on the host to bind the reverse proxy to.
+///
+/// # Notes
+///
+/// The name of the container will always be `local_llm_reverse_proxy`.
+async fn create_reverse_proxy_container(
+    docker: &Docker,
+    reverse_proxy: &ReverseProxyConfig,
+    host_port: u16,
+) -> Result<(), AppError> {
+    let container_name = get_container_name(REVERSE_PROXY_SERVICE_NAME);
+
+    let mut create_container_opts = CreateContainerOptionsBuilder::new().name(&container_name);
+
+    if let Some(platform) = &reverse_proxy.platform {
+        create_container_opts = create_container_opts.platform(platform);
+    }
+
+    let create_container_opts = create_container_opts.build();
+
+    let container_env = get_reverse_proxy_env(reverse_proxy);
+
+    // The proxy only needs to reach Open WebUI, so it isn't added to the backend network.
+    let mut networks = HashMap::<String, EndpointSettings>::new();
+    networks.insert(
+        "local_llm_frontend".to_string(),
+        EndpointSettings::default(),
+    );
+
+    let networking_config = NetworkingConfig {
+        endpoints_config: Some(networks),
+    };
+
+    let container_port = format!("{}/tcp", reverse_proxy.container_port);
+
+    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
+    container_ports.insert(container_port.clone(), HashMap::default());
+
+    let mut port_binds = HashMap::<String, Option<Vec<PortBinding>>>::new();
+    port_binds.insert(
+        container_port,
+        Some(vec![PortBinding {
+            host_port: Some(host_port.to_string()),
+            ..Default::default()
+        }]),
+    );
+
+    check_mount_collisions(
+        &get_container_name(REVERSE_PROXY_SERVICE_NAME),
+        &get_volume_binding_mounts(&reverse_proxy.volume_bindings),
+    )?;
+
+    let host_binds = get_volume_binds(&reverse_proxy.volume_bindings);
+
+    let host_config = HostConfig {
+        binds: host_binds,
+        port_bindings: Some(port_binds),
+        ..Default::default()
+    };
+
+    let container_config = ContainerCreateBody {
+        image: Some(reverse_proxy.image.clone()),
+        cmd: reverse_proxy.cmd.clone(),
+        env: Some(container_env),
+        networking_config: Some(networking_config),
+        exposed_ports: Some(container_ports),
+        host_config: Some(host_config),
+        labels: Some(build_container_labels(&reverse_proxy.labels)),
+        ..Default::default()
+    };
+
+    docker
+        .create_container(Some(create_container_opts), container_config)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
+
+    docker
+        .start_container(&container_name, Some(start_container_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    Ok(())
+}
+
+/// Creates and starts the embeddings backend container with Docker (or any Docker-compatible API).
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `embeddings_backend` - The embeddings backend config.
+///
+/// # Notes
+///
+/// The name of the container will always be `local_llm_embeddings`.
+async fn create_embeddings_backend_container(
+    docker: &Docker,
+    embeddings_backend: &EmbeddingsBackendConfig,
+) -> Result<(), AppError> {
+    let container_name = get_container_name(EMBEDDINGS_BACKEND_SERVICE_NAME);
+
+    let mut create_container_opts = CreateContainerOptionsBuilder::new().name(&container_name);
+
+    if let Some(platform) = &embeddings_backend.platform {
+        create_container_opts = create_container_opts.platform(platform);
+    }
+
+    let create_container_opts = create_container_opts.build();
+
+    // Only Open WebUI uses the service, so it's only reachable over the backend network.
+    let mut networks = HashMap::<String, EndpointSettings>::new();
+    networks.insert("local_llm_backend".to_string(), EndpointSettings::default());
+
+    let networking_config = NetworkingConfig {
+        endpoints_config: Some(networks),
+    };
+
+    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
+    container_ports.insert(format!("{}/tcp", embeddings_backend.port), HashMap::default());
+
+    let container_config = ContainerCreateBody {
+        image: Some(embeddings_backend.image.clone()),
+        cmd: embeddings_backend.cmd.clone(),
+        env: embeddings_backend.env.clone(),
+        networking_config: Some(networking_config),
+        exposed_ports: Some(container_ports),
+        labels: Some(build_container_labels(&None)),
+        ..Default::default()
+    };
+
+    docker
+        .create_container(Some(create_container_opts), container_config)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
+
+    docker
+        .start_container(&container_name, Some(start_container_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    Ok(())
+}
+
+/// Creates and starts the Apache Tika container with Docker (or any Docker-compatible API).
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+///
+/// # Notes
+///
+/// The name of the container will always be `local_llm_tika`.
+async fn create_tika_container(docker: &Docker, app_config: &LlmChatConfig) -> Result<(), AppError> {
+    let tika_image = get_tika_image(app_config);
+
+    let mut create_container_opts = CreateContainerOptionsBuilder::new().name("local_llm_tika");
+
+    if let Some(platform) = &app_config.platform {
+        create_container_opts = create_container_opts.platform(platform);
+    }
+
+    let create_container_opts = create_container_opts.build();
+
+    let mut networks = HashMap::<String, EndpointSettings>::new();
+    networks.insert("local_llm_backend".to_string(), EndpointSettings::default());
+
+    let networking_config = NetworkingConfig {
+        endpoints_config: Some(networks),
+    };
+
+    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
+    container_ports.insert("9998/tcp".to_string(), HashMap::default());
+
+    let host_config = match app_config.tika_host_port {
+        Some(tika_host_port) => {
+            if !utils::is_host_port_available(tika_host_port) {
+                return Err(AppError::GenericError(format!(
+                    "Port {} for Tika is already in use by another application",
+                    tika_host_port
+                )));
+            }
+
+            let mut port_binds = HashMap::<String, Option<Vec<PortBinding>>>::new();
+            port_binds.insert(
+                "9998/tcp".to_string(),
+                Some(vec![PortBinding {
+                    host_ip: Some("127.0.0.1".to_string()),
+                    host_port: Some(tika_host_port.to_string()),
+                }]),
+            );
+
+            Some(HostConfig {
+                port_bindings: Some(port_binds),
+                ..Default::default()
+            })
+        }
+
+        None => None,
+    };
+
+    let container_config = ContainerCreateBody {
+        image: Some(tika_image),
+        networking_config: Some(networking_config),
+        exposed_ports: Some(container_ports),
+        host_config,
+        labels: Some(build_container_labels(&app_config.labels)),
+        ..Default::default()
+    };
+
+    docker
+        .create_container(Some(create_container_opts), container_config)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
+
+    docker
+        .start_container("local_llm_tika", Some(start_container_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    Ok(())
+}
+
+/// Handles Tika failing to start.
+///
+/// When `tika_required` is set, the error is returned. Otherwise, the partly created
+/// container is removed and a warning is added to the summary, since Open WebUI runs
+/// fine without document extraction.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `infrastructure_summary` - The summary to add the warning to.
+/// * `error` - The error Tika failed with.
+async fn handle_tika_failure(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    infrastructure_summary: &mut InfrastructureSummary,
+    error: AppError,
+) -> Result<(), AppError> {
+    if app_config.tika_required {
+        return Err(error);
+    }
+
+    if let Err(remove_err) = ensure_container_removed(docker, "local_llm_tika").await {
+        println!("Warning: Failed to remove the Tika container: {}", remove_err);
+    }
+
+    infrastructure_summary.add_warning(format!(
+        "Tika failed to start, so document extraction won't be available: {}",
+        error
+    ));
+
+    Ok(())
+}
+
+/// Gets the environment variables for an extra backend service's container, including its secrets.
+///
+/// # Arguments
+///
+/// * `extra_service` - The extra service config.
+/// * `proxy_env` - The proxy environment variables for the containers.
+fn get_extra_service_env(
+    extra_service: &LlmChatConfigExtraBackendService,
+    proxy_env: &[String],
+) -> Result<Option<Vec<String>>, AppError> {
+    let secrets = match &extra_service.secrets_file {
+        Some(secrets_file) => utils::load_secrets_file(Path::new(secrets_file))?,
+        None => Secrets::default(),
+    };
+
+    Ok(extra_service.get_env(proxy_env, &secrets))
+}
+
+/// Gets the security options for the seccomp and AppArmor profiles of an extra backend service.
+///
+/// Docker expects the contents of a seccomp profile rather than its path,
+/// so the profile is read here.
+///
+/// # Arguments
+///
+/// * `extra_service` - The extra service config.
+fn get_extra_service_security_opt(
+    extra_service: &LlmChatConfigExtraBackendService,
+) -> Result<Option<Vec<String>>, AppError> {
+    let mut security_opt = Vec::new();
+
+    if let Some(seccomp_profile) = &extra_service.seccomp_profile {
+        let seccomp_profile = match seccomp_profile.as_str() {
+            UNCONFINED_SECURITY_PROFILE => UNCONFINED_SECURITY_PROFILE.to_string(),
+
+            seccomp_profile_path => fs::read_to_string(seccomp_profile_path).map_err(|e| {
+                AppError::ConfigError(format!(
+                    "The seccomp profile '{}' for '{}' can't be read: {}",
+                    seccomp_profile_path, extra_service.name, e
+                ))
+            })?,
+        };
+
+        security_opt.push(format!("seccomp={}", seccomp_profile));
+    }
+
+    if let Some(apparmor_profile) = &extra_service.apparmor_profile {
+        security_opt.push(format!("apparmor={}", apparmor_profile));
+    }
+
+    match security_opt.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(security_opt)),
+    }
+}
+
+/// Creates and starts an extra backend container with Docker (or any Docker-compatible API).
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `extra_service` - The extra service config.
+///
+/// # Notes
+///
+/// The name of the container will always be `local_llm_{name}`.
+async fn create_extra_service_container(
+    docker: Docker,
+    extra_service: LlmChatConfigExtraBackendService,
+    proxy_env: Vec<String>,
+) -> Result<(), AppError> {
+    let container_name = get_container_name(&extra_service.name);
+
+    let mut create_container_opts = CreateContainerOptionsBuilder::new().name(&container_name);
+
+    if let Some(platform) = &extra_service.platform {
+        create_container_opts = create_container_opts.platform(platform);
+    }
+
+    let create_container_opts = create_container_opts.build();
+
+    let container_env = get_extra_service_env(&extra_service, &proxy_env)?;
+
+    let mut networks = HashMap::<String, EndpointSettings>::new();
+    networks.insert(
+        "local_llm_backend".to_string(),
+        EndpointSettings {
+            aliases: extra_service.network_aliases.clone(),
+            ..Default::default()
+        },
+    );
+
+    let networking_config = NetworkingConfig {
+        endpoints_config: Some(networks),
+    };
+
+    let mut container_ports = HashMap::<String, HashMap<(), ()>>::new();
+    if let Some(ports) = extra_service.ports.clone() {
+        for port in ports {
+            container_ports.insert(port, HashMap::default());
+        }
+    }
+
+    let tmpfs = get_tmpfs_mounts(&extra_service.tmpfs);
+
+    let mut mounts = get_volume_binding_mounts(&extra_service.volume_bindings);
+    mounts.extend(
+        tmpfs
+            .iter()
+            .flatten()
+            .map(|(container_path, _)| ("tmpfs", container_path.as_str())),
+    );
+
+    check_mount_collisions(&get_container_name(&extra_service.name), &mounts)?;
+
+    let host_binds = get_volume_binds(&extra_service.volume_bindings);
+
+    let ulimits = extra_service.ulimits.as_ref().map(|ulimits| {
+        ulimits
+            .iter()
+            .map(|ulimit| ResourcesUlimits {
+                name: Some(ulimit.name.clone()),
+                soft: Some(ulimit.soft),
+                hard: Some(ulimit.hard),
+            })
+            .collect::<Vec<ResourcesUlimits>>()
+    });
+
+    let device_requests = extra_service.gpu.as_ref().map(build_device_requests);
+
+    let security_opt = get_extra_service_security_opt(&extra_service)?;
+
+    let devices = extra_service.devices.as_ref().map(|devices| {
+        devices
+            .iter()
+            .map(|device| DeviceMapping {
+                path_on_host: Some(device.host_path.clone()),
+                path_in_container: Some(
+                    device.container_path.clone().unwrap_or_else(|| device.host_path.clone()),
+                ),
+                cgroup_permissions: Some(
+                    device.cgroup_permissions.clone().unwrap_or_else(|| "rwm".to_string()),
+                ),
+            })
+            .collect::<Vec<DeviceMapping>>()
+    });
+
+    let has_host_config = host_binds.is_some()
+        || ulimits.is_some()
+        || device_requests.is_some()
+        || extra_service.init.is_some()
+        || extra_service.oom_kill_disable.is_some()
+        || extra_service.oom_score_adj.is_some()
+        || extra_service.cpuset_cpus.is_some()
+        || security_opt.is_some()
+        || tmpfs.is_some()
+        || devices.is_some()
+        || extra_service.group_add.is_some();
+
+    let host_config = match has_host_config {
+        false => None,
+
+        true => Some(HostConfig {
+            binds: host_binds,
+            ulimits,
+            device_requests,
+            init: extra_service.init,
+            oom_kill_disable: extra_service.oom_kill_disable,
+            oom_score_adj: extra_service.oom_score_adj,
+            cpuset_cpus: extra_service.cpuset_cpus.clone(),
+            security_opt,
+            tmpfs: tmpfs.map(|tmpfs| tmpfs.into_iter().collect()),
+            devices,
+            group_add: extra_service.group_add.clone(),
+            ..Default::default()
+        }),
+    };
+
+    // Docker treats an empty entrypoint as not set, so an empty string is used to clear it.
+    let entrypoint = extra_service
+        .entrypoint
+        .clone()
+        .map(|entrypoint| match entrypoint.is_empty() {
+            true => vec![String::new()],
+            false => entrypoint,
+        });
+
+    let container_config = ContainerCreateBody {
+        image: Some(extra_service.image.clone()),
+        entrypoint,
+        cmd: extra_service.cmd.clone(),
+        env: container_env,
+        networking_config: Some(networking_config),
+        exposed_ports: Some(container_ports),
+        host_config: host_config,
+        user: extra_service.user.clone(),
+        hostname: extra_service.hostname.clone(),
+        domainname: extra_service.domainname.clone(),
+        working_dir: extra_service.working_directory.clone(),
+        labels: Some(build_container_labels(&extra_service.labels)),
+        ..Default::default()
+    };
+
+    docker
+        .create_container(Some(create_container_opts), container_config)
+        .await
+        .map_err(|e| docker::map_docker_error(&docker, e))?;
+
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
+
+    docker
+        .start_container(&container_name, Some(start_container_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(&docker, e))?;
+
+    Ok(())
+}
+
+/// What was removed when an extra backend service was purged.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PurgeServiceResult {
+    /// Whether the service's container existed and was removed.
+    #[serde(rename = "containerRemoved")]
+    pub container_removed: bool,
+
+    /// The host paths created for the service that were removed.
+    #[serde(rename = "removedPaths")]
+    pub removed_paths: Vec<String>,
+
+    /// The named volumes created for the service that were removed.
+    #[serde(rename = "removedVolumes")]
+    pub removed_volumes: Vec<String>,
+
+    /// The paths, volumes, or image that couldn't be removed, with why.
+    ///
+    /// The rest are still removed, and the ones that failed are kept tracked,
+    /// so purging the service again retries them.
+    #[serde(rename = "errors")]
+    pub errors: Vec<String>,
+
+    /// Whether the service's image was removed.
+    #[serde(rename = "imageRemoved")]
+    pub image_removed: bool,
+}
+
+/// Stops and removes an extra backend service's container, even if it's no longer in the config.
+///
+/// Returns the image the container was created from, or `None` if it didn't exist.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `service_name` - The name of the extra backend service.
+pub async fn remove_extra_service_container(
+    docker: &Docker,
+    service_name: &str,
+) -> Result<Option<String>, AppError> {
+    if matches!(
+        service_name,
+        "openwebui" | "tika" | REVERSE_PROXY_SERVICE_NAME | EMBEDDINGS_BACKEND_SERVICE_NAME
+    ) {
+        return Err(AppError::ConfigError(format!(
+            "'{}' isn't an extra backend service, so it can't be purged",
+            service_name
+        )));
+    }
+
+    let container_name = get_container_name(service_name);
+
+    let image = match docker
+        .inspect_container(&container_name, None::<InspectContainerOptions>)
+        .await
+    {
+        Ok(container) => container
+            .config
+            .and_then(|config| config.image)
+            .unwrap_or_default(),
+
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => return Ok(None),
+
+        Err(err) => return Err(docker::map_docker_error(docker, err)),
+    };
+
+    ensure_container_removed(docker, &container_name).await?;
+
+    Ok(Some(image))
+}
+
+/// Checks if a volume binding's host path is a named volume, rather than a path on the host.
+///
+/// Docker treats any host path that isn't absolute as the name of a volume.
+///
+/// # Arguments
+///
+/// * `host_path` - The host path from the volume binding.
+pub fn is_named_volume(host_path: &str) -> bool {
+    !Path::new(host_path).is_absolute()
+}
+
+/// Gets the named volumes of the enabled extra backend services that don't exist yet,
+/// since Docker creates them for the service when its container is created.
+///
+/// Returns the name of each service, along with the name of the volume.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn get_missing_service_volumes(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+) -> Result<Vec<(String, String)>, AppError> {
+    let mut missing_volumes = Vec::new();
+
+    for extra_service in app_config.get_enabled_extra_services() {
+        for volume_binding in extra_service.volume_bindings.iter().flatten() {
+            if !is_named_volume(&volume_binding.host_path) {
+                continue;
+            }
+
+            match docker.inspect_volume(&volume_binding.host_path).await {
+                Ok(_) => {}
+
+                Err(bollard::errors::Error::DockerResponseServerError {
+                    status_code: 404, ..
+                }) => missing_volumes.push((
+                    extra_service.name.clone(),
+                    volume_binding.host_path.clone(),
+                )),
+
+                Err(err) => return Err(docker::map_docker_error(docker, err)),
+            }
+        }
+    }
+
+    Ok(missing_volumes)
+}
+
+/// Removes a named volume, returning whether it existed.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `volume_name` - The name of the volume.
+pub async fn remove_volume(docker: &Docker, volume_name: &str) -> Result<bool, AppError> {
+    // bollard's `remove_volume` still takes the deprecated options type.
+    #[allow(deprecated)]
+    let remove_volume_opts = None::<bollard::volume::RemoveVolumeOptions>;
+
+    match docker.remove_volume(volume_name, remove_volume_opts).await {
+        Ok(_) => {
+            println!("Removed volume '{}'", volume_name);
+
+            Ok(true)
+        }
+
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(false),
+
+        Err(err) => Err(docker::map_docker_error(docker, err)),
+    }
+}
+
+/// Removes an image, unless a container is still using it.
+///
+/// Returns whether the image was removed.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `image` - The image to remove.
+pub async fn remove_image_if_unused(docker: &Docker, image: &str) -> Result<bool, AppError> {
+    match docker
+        .remove_image(image, None::<RemoveImageOptions>, None)
+        .await
+    {
+        Ok(_) => {
+            println!("Removed image '{}'", image);
+
+            Ok(true)
+        }
+
+        // The image was already removed outside of the app.
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(false),
+
+        // Another container is using the image.
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 409, ..
+        }) => {
+            println!("Image '{}' is in use by another container, so it was kept", image);
+
+            Ok(false)
+        }
+
+        Err(err) => Err(docker::map_docker_error(docker, err)),
+    }
+}
+
+/// Cleans up Docker (or any Docker-compatible API) resources created by the application.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `remove_networks` - Whether to remove the networks too. They're kept between launches
+///   to be reused, so they're only removed on a full teardown.
+pub async fn cleanup_infrastructure(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    remove_networks: bool,
+) -> Result<(), AppError> {
+    println!("Deleting containers...");
+    delete_containers(docker, app_config).await?;
+
+    if remove_networks {
+        println!("Deleting networks...");
+        delete_networks(docker).await?;
+    }
+
+    Ok(())
+}
+
+/// Delete the networks created by the application from Docker (or any Docker-compatible API).
+///
+/// The networks are found by the management label.
+async fn delete_networks(docker: &Docker) -> Result<(), AppError> {
     let mut network_filters = HashMap::<String, Vec<String>>::new();
-    network_filters.insert("name".to_string(), vec!["local_llm_".to_string()]);
+    network_filters.insert(
+        "label".to_string(),
+        vec![format!("{}=true", MANAGED_LABEL_KEY)],
+    );
 
     let list_network_opts = ListNetworksOptionsBuilder::new()
         .filters(&network_filters)
         .build();
 
-    let container_networks = docker
-        .list_networks(Some(list_network_opts))
+    let container_networks = docker
+        .list_networks(Some(list_network_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    for network in container_networks {
+        let network_name = network.name.unwrap();
+
+        docker
+            .remove_network(&network_name)
+            .await
+            .map_err(|e| docker::map_docker_error(docker, e))?;
+
+        println!("Removed network '{}'", &network_name);
+    }
+
+    Ok(())
+}
+
+/// Delete the containers created by the application from Docker (or any Docker-compatible API).
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+async fn delete_containers(docker: &Docker, app_config: &LlmChatConfig) -> Result<(), AppError> {
+    let mut container_names = get_managed_resources(app_config).containers;
+
+    // Disabled services are included, so a service that was just disabled is torn down too.
+    for extra_service in app_config.extra_backend_services.iter().flatten() {
+        if !extra_service.enabled {
+            container_names.push(get_container_name(&extra_service.name));
+        }
+    }
+
+    let mut container_filters = HashMap::<String, Vec<String>>::new();
+    container_filters.insert("name".to_string(), container_names);
+
+    println!("Getting containers");
+    let list_containers_opts = ListContainersOptionsBuilder::new()
+        .all(true)
+        .filters(&container_filters)
+        .build();
+
+    let containers = docker
+        .list_containers(Some(list_containers_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let mut delete_tasks = Vec::with_capacity(containers.len());
+
+    for container in containers {
+        delete_tasks.push(tokio::spawn(delete_container(
+            docker.clone(),
+            container,
+            app_config.openwebui_stop_timeout_secs,
+        )));
+    }
+
+    for task in delete_tasks {
+        task.await.unwrap_or_else(|e| {
+            Err(AppError::GenericError(format!(
+                "The task for deleting the container failed: {}",
+                e
+            )))
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Stops and removes a container by name, if it exists.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the container.
+pub async fn ensure_container_removed(docker: &Docker, container_name: &str) -> Result<(), AppError> {
+    let stop_container_opts = StopContainerOptionsBuilder::new().build();
+
+    let _ = docker
+        .stop_container(container_name, Some(stop_container_opts))
+        .await;
+
+    let remove_container_opts = RemoveContainerOptionsBuilder::new().force(true).build();
+
+    match docker
+        .remove_container(container_name, Some(remove_container_opts))
+        .await
+    {
+        Ok(_) => {
+            println!("Removed container '{}'", container_name);
+
+            Ok(())
+        }
+
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => Ok(()),
+
+        Err(err) => Err(docker::map_docker_error(docker, err)),
+    }
+}
+
+/// Stops and removes a container.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container` - The container to remove.
+/// * `openwebui_stop_timeout_secs` - How long to wait for Open WebUI to stop, if it's the Open WebUI container.
+async fn delete_container(
+    docker: Docker,
+    container: ContainerSummary,
+    openwebui_stop_timeout_secs: u64,
+) -> Result<(), AppError> {
+    // Fall back to the container ID if the container doesn't have a name,
+    // which can happen if it's in the middle of being removed.
+    let container_name = container
+        .names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|name| name.trim_matches('/').to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| container.id.clone());
+
+    let Some(container_name) = container_name else {
+        println!("Skipping a container without a name or ID");
+
+        return Ok(());
+    };
+
+    let mut stop_container_opts = StopContainerOptionsBuilder::new();
+
+    // Containers created before `openwebui_stop_timeout_secs` was set don't have it
+    // as their stop timeout, so it's passed explicitly.
+    if container_name == get_container_name("openwebui") {
+        stop_container_opts = stop_container_opts.t(openwebui_stop_timeout_secs as i32);
+    }
+
+    let stop_container_opts = stop_container_opts.build();
+
+    let _ = docker
+        .stop_container(&container_name, Some(stop_container_opts))
+        .await;
+
+    let remove_container_opts = RemoveContainerOptionsBuilder::new().force(true).build();
+
+    match docker
+        .remove_container(&container_name, Some(remove_container_opts))
+        .await
+    {
+        Ok(_) => println!("Removed container '{}'", &container_name),
+
+        // The container was already removed.
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => {}
+
+        Err(err) => return Err(docker::map_docker_error(&docker, err)),
+    }
+
+    Ok(())
+}
+
+/// The names of the containers and networks the app manages for a config.
+#[derive(Serialize, Debug, Clone)]
+pub struct ManagedResources {
+    /// The names of the containers, including any extra backend services.
+    #[serde(rename = "containers")]
+    pub containers: Vec<String>,
+
+    /// The names of the networks.
+    #[serde(rename = "networks")]
+    pub networks: Vec<String>,
+}
+
+/// Gets the name of the container for a service.
+///
+/// # Arguments
+///
+/// * `service_name` - The name of the service (e.g. `openwebui` or the name of an extra backend service).
+pub fn get_container_name(service_name: &str) -> String {
+    format!("local_llm_{}", service_name)
+}
+
+/// Gets the names of the containers and networks the app manages for a config.
+///
+/// Disabled extra backend services aren't included.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+pub fn get_managed_resources(app_config: &LlmChatConfig) -> ManagedResources {
+    let mut containers = vec![get_container_name("openwebui"), get_container_name("tika")];
+
+    if app_config.reverse_proxy.is_some() {
+        containers.push(get_container_name(REVERSE_PROXY_SERVICE_NAME));
+    }
+
+    if app_config.embeddings_backend.is_some() {
+        containers.push(get_container_name(EMBEDDINGS_BACKEND_SERVICE_NAME));
+    }
+
+    for extra_service in app_config.get_enabled_extra_services() {
+        containers.push(get_container_name(&extra_service.name));
+    }
+
+    ManagedResources {
+        containers,
+        networks: vec![
+            "local_llm_frontend".to_string(),
+            "local_llm_backend".to_string(),
+        ],
+    }
+}
+
+/// Represents the status of a container managed by the application.
+#[derive(Serialize, Debug, Clone)]
+pub struct ContainerStatus {
+    /// The name of the container.
+    #[serde(rename = "name")]
+    pub name: String,
+
+    /// The state of the container (e.g. `running`).
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
+    /// The human-readable status of the container.
+    #[serde(rename = "status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+/// Gets the status of every container managed by the application.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+pub async fn get_container_statuses(docker: &Docker) -> Result<Vec<ContainerStatus>, AppError> {
+    let mut container_filters = HashMap::<String, Vec<String>>::new();
+    container_filters.insert(
+        "label".to_string(),
+        vec![format!("{}=true", MANAGED_LABEL_KEY)],
+    );
+
+    let list_containers_opts = ListContainersOptionsBuilder::new()
+        .all(true)
+        .filters(&container_filters)
+        .build();
+
+    let containers = docker
+        .list_containers(Some(list_containers_opts))
         .await
-        .map_err(|e| AppError::DockerError(e))?;
+        .map_err(|e| docker::map_docker_error(docker, e))?;
 
-    for network in container_networks {
-        let network_name = network.name.unwrap();
+    let mut container_statuses = Vec::with_capacity(containers.len());
 
-        docker
-            .remove_network(&network_name)
+    for container in containers {
+        let container_name = match container.names.as_ref().and_then(|names| names.first()) {
+            Some(name) => name.trim_matches('/').to_string(),
+            None => continue,
+        };
+
+        container_statuses.push(ContainerStatus {
+            name: container_name,
+            state: container.state.map(|state| state.to_string()),
+            status: container.status,
+        });
+    }
+
+    Ok(container_statuses)
+}
+
+/// A port on a running container and the host port Docker bound it to.
+#[derive(Serialize, Debug, Clone)]
+pub struct PortMapping {
+    /// The name of the container.
+    #[serde(rename = "containerName")]
+    pub container_name: String,
+
+    /// The port in the container, with its protocol (e.g. `8080/tcp`).
+    #[serde(rename = "containerPort")]
+    pub container_port: String,
+
+    /// The host IP address the port is bound to.
+    #[serde(rename = "hostIp", skip_serializing_if = "Option::is_none")]
+    pub host_ip: Option<String>,
+
+    /// The host port the port is bound to, or `None` if it isn't published on the host.
+    #[serde(rename = "hostPort", skip_serializing_if = "Option::is_none")]
+    pub host_port: Option<String>,
+}
+
+/// Gets the port bindings Docker actually applied to the containers managed by the app.
+///
+/// These can differ from the configured ports, such as when a port was picked automatically.
+/// Containers that don't exist are skipped.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn get_port_mappings(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+) -> Result<Vec<PortMapping>, AppError> {
+    let mut port_mappings = Vec::<PortMapping>::new();
+
+    for container_name in get_managed_resources(app_config).containers {
+        let container = match docker
+            .inspect_container(&container_name, None::<InspectContainerOptions>)
             .await
-            .map_err(|e| AppError::DockerError(e))?;
+        {
+            Ok(container) => container,
 
-        println!("Removed network '{}'", &network_name);
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => continue,
+
+            Err(err) => return Err(docker::map_docker_error(docker, err)),
+        };
+
+        let ports = container
+            .network_settings
+            .and_then(|network_settings| network_settings.ports)
+            .unwrap_or_default();
+
+        let mut container_ports = ports.into_iter().collect::<Vec<_>>();
+        container_ports.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (container_port, host_bindings) in container_ports {
+            match host_bindings.filter(|host_bindings| !host_bindings.is_empty()) {
+                Some(host_bindings) => {
+                    for host_binding in host_bindings {
+                        port_mappings.push(PortMapping {
+                            container_name: container_name.clone(),
+                            container_port: container_port.clone(),
+                            host_ip: host_binding.host_ip,
+                            host_port: host_binding.host_port,
+                        });
+                    }
+                }
+
+                None => port_mappings.push(PortMapping {
+                    container_name: container_name.clone(),
+                    container_port,
+                    host_ip: None,
+                    host_port: None,
+                }),
+            }
+        }
+    }
+
+    Ok(port_mappings)
+}
+
+/// The health of one of the containers managed by the app.
+#[derive(Serialize, Debug, Clone)]
+pub struct ComponentHealth {
+    /// The name of the container.
+    #[serde(rename = "name")]
+    pub name: String,
+
+    /// The state of the container (e.g. `running`), or `None` if it doesn't exist.
+    #[serde(rename = "state", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+
+    /// The result of the image's health check (e.g. `healthy`), if it has one.
+    #[serde(rename = "health", skip_serializing_if = "Option::is_none")]
+    pub health: Option<String>,
+
+    /// Whether the container is running and, if it has a health check, passing it.
+    #[serde(rename = "healthy")]
+    pub healthy: bool,
+
+    /// Whether the stack needs this container to be healthy to be healthy itself.
+    #[serde(rename = "required")]
+    pub required: bool,
+}
+
+/// The health of the whole stack, for a single "is everything OK?" indicator.
+#[derive(Serialize, Debug, Clone)]
+pub struct StackHealth {
+    /// Whether Open WebUI is reachable and every required container is healthy.
+    #[serde(rename = "healthy")]
+    pub healthy: bool,
+
+    /// Whether Open WebUI's health endpoint responded as healthy.
+    #[serde(rename = "openwebuiReachable")]
+    pub openwebui_reachable: bool,
+
+    /// The health of each container managed by the app.
+    #[serde(rename = "components")]
+    pub components: Vec<ComponentHealth>,
+}
+
+/// Gets the health of the whole stack, combining Open WebUI's health endpoint with
+/// the state and health check of each container.
+///
+/// Tika is only required when `tika_required` is set, since Open WebUI runs without it.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `webui_url` - The URL for Open WebUI.
+pub async fn get_stack_health(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    webui_url: &str,
+) -> Result<StackHealth, AppError> {
+    let mut components = Vec::<ComponentHealth>::new();
+
+    for container_name in get_managed_resources(app_config).containers {
+        let required = container_name != get_container_name("tika") || app_config.tika_required;
+
+        let container_state = match docker
+            .inspect_container(&container_name, None::<InspectContainerOptions>)
+            .await
+        {
+            Ok(container) => container.state,
+
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            }) => {
+                components.push(ComponentHealth {
+                    name: container_name,
+                    state: None,
+                    health: None,
+                    healthy: false,
+                    required,
+                });
+
+                continue;
+            }
+
+            Err(err) => return Err(docker::map_docker_error(docker, err)),
+        };
+
+        let is_running = container_state
+            .as_ref()
+            .and_then(|state| state.running)
+            .unwrap_or(false);
+
+        // Containers whose image has no health check report a status of `none`.
+        let health = container_state
+            .as_ref()
+            .and_then(|state| state.health.as_ref())
+            .and_then(|health| health.status)
+            .filter(|status| !matches!(status, HealthStatusEnum::NONE | HealthStatusEnum::EMPTY));
+
+        components.push(ComponentHealth {
+            name: container_name,
+            state: container_state
+                .and_then(|state| state.status)
+                .map(|status| status.to_string()),
+            health: health.map(|health| health.to_string()),
+            healthy: is_running && health.is_none_or(|health| health == HealthStatusEnum::HEALTHY),
+            required,
+        });
+    }
+
+    let openwebui_reachable = utils::is_openwebui_ready(webui_url).await?;
+
+    let healthy = openwebui_reachable
+        && components
+            .iter()
+            .all(|component| component.healthy || !component.required);
+
+    Ok(StackHealth {
+        healthy,
+        openwebui_reachable,
+        components,
+    })
+}
+
+/// Pauses the running containers managed by the app, freezing their processes.
+///
+/// Containers that don't exist or aren't running are skipped.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn pause_containers(docker: &Docker, app_config: &LlmChatConfig) -> Result<(), AppError> {
+    for container_name in get_managed_resources(app_config).containers {
+        match docker.pause_container(&container_name).await {
+            Ok(_) => println!("Paused container '{}'", container_name),
+
+            // The container doesn't exist, isn't running, or is already paused.
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404 | 409,
+                ..
+            }) => {}
+
+            Err(err) => return Err(docker::map_docker_error(docker, err)),
+        }
     }
 
     Ok(())
 }
 
-/// Delete the containers created by the application from Docker (or any Docker-compatible API).
+/// Unpauses the paused containers managed by the app.
+///
+/// Containers that don't exist or aren't paused are skipped.
 ///
 /// # Arguments
 ///
+/// * `docker` - The Docker client.
 /// * `app_config` - The application configuration.
-async fn delete_containers(app_config: &LlmChatConfig) -> Result<(), AppError> {
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+pub async fn unpause_containers(docker: &Docker, app_config: &LlmChatConfig) -> Result<(), AppError> {
+    for container_name in get_managed_resources(app_config).containers {
+        match docker.unpause_container(&container_name).await {
+            Ok(_) => println!("Unpaused container '{}'", container_name),
 
-    let mut container_names = vec![
-        "local_llm_openwebui".to_string(),
-        "local_llm_tika".to_string(),
-    ];
+            // The container doesn't exist or isn't paused.
+            Err(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404 | 409,
+                ..
+            }) => {}
 
-    if let Some(extra_services) = app_config.extra_backend_services.clone() {
-        for extra_service in extra_services {
-            container_names.push(format!("local_llm_{}", extra_service.name));
+            Err(err) => return Err(docker::map_docker_error(docker, err)),
         }
     }
 
-    let mut container_filters = HashMap::<String, Vec<String>>::new();
-    container_filters.insert("name".to_string(), container_names);
+    Ok(())
+}
 
-    println!("Getting containers");
-    let list_containers_opts = ListContainersOptionsBuilder::new()
-        .all(true)
-        .filters(&container_filters)
+/// Checks whether any containers managed by the app exist, such as ones left behind
+/// by a previous session that crashed before it could clean up.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+pub async fn has_managed_containers(docker: &Docker) -> Result<bool, AppError> {
+    Ok(!get_container_statuses(docker).await?.is_empty())
+}
+
+/// The result of running a command in a container.
+#[derive(Serialize, Debug, Clone)]
+pub struct ExecResult {
+    /// The output written to stdout.
+    #[serde(rename = "stdout")]
+    pub stdout: String,
+
+    /// The output written to stderr.
+    #[serde(rename = "stderr")]
+    pub stderr: String,
+
+    /// The exit code of the command, if it was reported.
+    #[serde(rename = "exitCode", skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i64>,
+}
+
+/// Runs the init command in the Open WebUI container and waits for it to finish.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `init_command` - The command and args to run.
+pub async fn run_init_command(docker: &Docker, init_command: Vec<String>) -> Result<(), AppError> {
+    println!("Running init command: {}", init_command.join(" "));
+
+    let exec_result = exec_in_container(docker, "local_llm_openwebui", init_command).await?;
+
+    if !exec_result.stdout.is_empty() {
+        println!("Init command stdout:\n{}", exec_result.stdout);
+    }
+
+    if !exec_result.stderr.is_empty() {
+        println!("Init command stderr:\n{}", exec_result.stderr);
+    }
+
+    match exec_result.exit_code {
+        Some(0) => Ok(()),
+
+        exit_code => Err(AppError::InitCommandError(format!(
+            "exited with {}\n\n{}{}",
+            exit_code
+                .map(|exit_code| format!("code {}", exit_code))
+                .unwrap_or_else(|| "an unknown exit code".to_string()),
+            exec_result.stdout,
+            exec_result.stderr
+        ))),
+    }
+}
+
+/// Ensures a container is managed by the application.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the container.
+async fn ensure_container_is_managed(docker: &Docker, container_name: &str) -> Result<(), AppError> {
+    let container = docker
+        .inspect_container(container_name, Some(InspectContainerOptionsBuilder::new().build()))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let is_managed = container
+        .config
+        .as_ref()
+        .and_then(|config| config.labels.as_ref())
+        .is_some_and(|labels| labels.contains_key(MANAGED_LABEL_KEY));
+
+    if !is_managed {
+        return Err(AppError::GenericError(format!(
+            "Container '{}' is not managed by the app",
+            container_name
+        )));
+    }
+
+    Ok(())
+}
+
+/// Gets the last lines of a managed container's logs, without following them.
+///
+/// stdout and stderr are interleaved in the order Docker returns them.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the container.
+/// * `lines` - The maximum number of lines to get.
+pub async fn get_recent_logs(
+    docker: &Docker,
+    container_name: &str,
+    lines: usize,
+) -> Result<Vec<String>, AppError> {
+    ensure_container_is_managed(docker, container_name).await?;
+
+    let logs_opts = LogsOptionsBuilder::new()
+        .follow(false)
+        .stdout(true)
+        .stderr(true)
+        .tail(&lines.to_string())
         .build();
 
-    let containers = docker
-        .list_containers(Some(list_containers_opts))
+    let mut logs_stream = docker.logs(container_name, Some(logs_opts));
+
+    // Frames aren't guaranteed to line up with lines, so the output is buffered and split after.
+    let mut logs_output = String::new();
+
+    while let Some(msg) = logs_stream.next().await {
+        match msg.map_err(|e| docker::map_docker_error(docker, e))? {
+            LogOutput::StdOut { message }
+            | LogOutput::StdErr { message }
+            | LogOutput::Console { message } => {
+                logs_output.push_str(&String::from_utf8_lossy(&message))
+            }
+
+            _ => {}
+        }
+    }
+
+    let mut log_lines: Vec<String> = logs_output
+        .lines()
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .collect();
+
+    // The tail is applied to log entries, and a single entry can span more than one line.
+    if log_lines.len() > lines {
+        log_lines.drain(..log_lines.len() - lines);
+    }
+
+    Ok(log_lines)
+}
+
+/// Runs a command in a running container managed by the application and waits for it to finish.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the container.
+/// * `cmd` - The command and args to run.
+pub async fn exec_in_container(
+    docker: &Docker,
+    container_name: &str,
+    cmd: Vec<String>,
+) -> Result<ExecResult, AppError> {
+    if cmd.is_empty() {
+        return Err(AppError::GenericError(
+            "A command must be provided to run in the container".to_string(),
+        ));
+    }
+
+    // Only allow running commands in containers managed by the application.
+    ensure_container_is_managed(docker, container_name).await?;
+
+    let create_exec_opts = CreateExecOptions {
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        cmd: Some(cmd),
+        ..Default::default()
+    };
+
+    let exec = docker
+        .create_exec(container_name, create_exec_opts)
         .await
-        .map_err(|e| AppError::DockerError(e))?;
+        .map_err(|e| docker::map_docker_error(docker, e))?;
 
-    let mut delete_tasks = Vec::with_capacity(containers.len());
+    let mut stdout = String::new();
+    let mut stderr = String::new();
 
-    for container in containers {
-        delete_tasks.push(tokio::spawn(delete_container(container)));
+    if let StartExecResults::Attached { mut output, .. } = docker
+        .start_exec(&exec.id, None)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?
+    {
+        while let Some(msg) = output.next().await {
+            match msg.map_err(|e| docker::map_docker_error(docker, e))? {
+                LogOutput::StdOut { message } => stdout.push_str(&String::from_utf8_lossy(&message)),
+                LogOutput::StdErr { message } => stderr.push_str(&String::from_utf8_lossy(&message)),
+                _ => {}
+            }
+        }
     }
 
-    for task in delete_tasks {
-        task.await.unwrap()?;
+    // The output stream can close slightly before the exec is reported as finished.
+    loop {
+        let exec_inspect = docker
+            .inspect_exec(&exec.id)
+            .await
+            .map_err(|e| docker::map_docker_error(docker, e))?;
+
+        if !exec_inspect.running.unwrap_or(false) {
+            return Ok(ExecResult {
+                stdout,
+                stderr,
+                exit_code: exec_inspect.exit_code,
+            });
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
     }
+}
 
-    Ok(())
+/// The image used to check that the container engine can run containers.
+const SELF_TEST_IMAGE: &'static str = "docker.io/library/busybox:latest";
+
+/// The name of the throwaway container used to check the container engine.
+const SELF_TEST_CONTAINER_NAME: &'static str = "local_llm_self_test";
+
+/// The output the self-test container is expected to print.
+const SELF_TEST_EXPECTED_OUTPUT: &'static str = "localllm-self-test-ok";
+
+/// How long the self-test container has to run before the self-test fails.
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The result of checking that the container engine can run containers.
+#[derive(Serialize, Debug, Clone)]
+pub struct EngineSelfTestResult {
+    /// Whether the throwaway container ran and printed the expected output.
+    #[serde(rename = "passed")]
+    pub passed: bool,
+
+    /// What happened, or why it failed.
+    #[serde(rename = "detail")]
+    pub detail: String,
 }
 
-async fn delete_container(container: ContainerSummary) -> Result<(), AppError> {
-    let docker = Docker::connect_with_local_defaults().map_err(|e| AppError::DockerError(e))?;
+/// Checks that the container engine can actually run containers, not just respond to pings.
+///
+/// A tiny image is pulled and a throwaway container is run from it, which catches problems
+/// like a broken storage driver. The container is always removed afterwards.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `active_pulls` - Where the pull is tracked, so it can be cancelled.
+pub async fn engine_self_test(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    active_pulls: &ActivePulls,
+) -> EngineSelfTestResult {
+    let self_test_result = tokio::time::timeout(
+        SELF_TEST_TIMEOUT,
+        run_self_test_container(reporter, docker, app_config, active_pulls),
+    )
+    .await;
 
-    let container_names = container.names.unwrap();
-    let container_name = container_names.first().unwrap().trim_matches('/');
+    if let Err(remove_err) = ensure_container_removed(docker, SELF_TEST_CONTAINER_NAME).await {
+        println!("Warning: Failed to remove the self-test container: {}", remove_err);
+    }
 
-    let stop_container_opts = StopContainerOptionsBuilder::new().build();
+    match self_test_result {
+        Ok(Ok(output)) if output.trim() == SELF_TEST_EXPECTED_OUTPUT => EngineSelfTestResult {
+            passed: true,
+            detail: format!("Ran a container from '{}'", SELF_TEST_IMAGE),
+        },
 
-    let _ = docker
-        .stop_container(&container_name, Some(stop_container_opts))
-        .await;
+        Ok(Ok(output)) => EngineSelfTestResult {
+            passed: false,
+            detail: format!(
+                "The container from '{}' printed '{}' instead of '{}'",
+                SELF_TEST_IMAGE,
+                output.trim(),
+                SELF_TEST_EXPECTED_OUTPUT
+            ),
+        },
 
-    let remove_container_opts = RemoveContainerOptionsBuilder::new().force(true).build();
+        Ok(Err(err)) => EngineSelfTestResult {
+            passed: false,
+            detail: err.to_string(),
+        },
+
+        Err(_) => EngineSelfTestResult {
+            passed: false,
+            detail: format!(
+                "The container from '{}' didn't finish within {} seconds",
+                SELF_TEST_IMAGE,
+                SELF_TEST_TIMEOUT.as_secs()
+            ),
+        },
+    }
+}
+
+/// Pulls the self-test image, runs the self-test container until it exits, and gets its output.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `active_pulls` - Where the pull is tracked, so it can be cancelled.
+async fn run_self_test_container(
+    reporter: &dyn ProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    active_pulls: &ActivePulls,
+) -> Result<String, AppError> {
+    pull_optional_image(reporter, docker, app_config, SELF_TEST_IMAGE, active_pulls).await?;
+
+    // A container left over from an interrupted self-test would stop this one from being created.
+    ensure_container_removed(docker, SELF_TEST_CONTAINER_NAME).await?;
+
+    let mut create_container_opts =
+        CreateContainerOptionsBuilder::new().name(SELF_TEST_CONTAINER_NAME);
+
+    if let Some(platform) = &app_config.platform {
+        create_container_opts = create_container_opts.platform(platform);
+    }
+
+    let container_config = ContainerCreateBody {
+        image: Some(SELF_TEST_IMAGE.to_string()),
+        cmd: Some(vec!["echo".to_string(), SELF_TEST_EXPECTED_OUTPUT.to_string()]),
+        labels: Some(build_container_labels(&app_config.labels)),
+        ..Default::default()
+    };
 
     docker
-        .remove_container(&container_name, Some(remove_container_opts))
+        .create_container(Some(create_container_opts.build()), container_config)
         .await
-        .map_err(|e| AppError::DockerError(e))?;
+        .map_err(|e| docker::map_docker_error(docker, e))?;
 
-    println!("Removed container '{}'", &container_name);
+    let start_container_opts = StartContainerOptionsBuilder::new().build();
 
-    Ok(())
+    docker
+        .start_container(SELF_TEST_CONTAINER_NAME, Some(start_container_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let mut wait_stream =
+        docker.wait_container(SELF_TEST_CONTAINER_NAME, None::<WaitContainerOptions>);
+
+    while let Some(wait_result) = wait_stream.next().await {
+        wait_result.map_err(|e| docker::map_docker_error(docker, e))?;
+    }
+
+    let output = get_recent_logs(docker, SELF_TEST_CONTAINER_NAME, 10).await?;
+
+    Ok(output.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_expected_container() -> ExpectedContainer {
+        ExpectedContainer {
+            image: "ghcr.io/open-webui/open-webui:main".to_string(),
+            binds: vec![
+                "/data/openwebui:/app/backend/data".to_string(),
+                "/data/models:/models:ro".to_string(),
+            ],
+            host_ports: vec![8080],
+            env: vec!["PORT=8080".to_string(), "WEBUI_AUTH=False".to_string()],
+            labels: build_container_labels(&Some(HashMap::from([(
+                "com.example.team".to_string(),
+                "llm".to_string(),
+            )]))),
+        }
+    }
+
+    fn build_container(expected_container: &ExpectedContainer) -> ContainerInspectResponse {
+        let port_bindings = expected_container
+            .host_ports
+            .iter()
+            .map(|host_port| {
+                (
+                    format!("{}/tcp", host_port),
+                    Some(vec![PortBinding {
+                        host_ip: Some("127.0.0.1".to_string()),
+                        host_port: Some(host_port.to_string()),
+                    }]),
+                )
+            })
+            .collect();
+
+        // The image's own environment and labels are merged in when Docker creates the container.
+        let mut env = vec!["PATH=/usr/local/bin:/usr/bin".to_string()];
+        env.extend(expected_container.env.clone());
+
+        let mut labels = expected_container.labels.clone();
+        labels.insert("org.opencontainers.image.source".to_string(), "open-webui".to_string());
+
+        ContainerInspectResponse {
+            config: Some(ContainerConfig {
+                image: Some(expected_container.image.clone()),
+                env: Some(env),
+                labels: Some(labels),
+                ..Default::default()
+            }),
+            host_config: Some(HostConfig {
+                binds: Some(expected_container.binds.iter().rev().cloned().collect()),
+                port_bindings: Some(port_bindings),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn matching_container_is_same_image_and_config() {
+        let expected_container = build_expected_container();
+        let container = build_container(&expected_container);
+
+        assert!(is_same_image(&container, &expected_container.image));
+        assert!(is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn different_image_is_not_same_image() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container.config.as_mut().unwrap().image =
+            Some("ghcr.io/open-webui/open-webui:cuda".to_string());
+
+        assert!(!is_same_image(&container, &expected_container.image));
+        assert!(is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn missing_image_is_not_same_image() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container.config = None;
+
+        assert!(!is_same_image(&container, &expected_container.image));
+    }
+
+    #[test]
+    fn changed_env_value_is_not_same_config() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container.config.as_mut().unwrap().env =
+            Some(vec!["PORT=8080".to_string(), "WEBUI_AUTH=True".to_string()]);
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn missing_env_var_is_not_same_config() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container.config.as_mut().unwrap().env = Some(vec!["PORT=8080".to_string()]);
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn later_env_var_takes_precedence() {
+        assert!(has_expected_env(
+            &["PORT=3000".to_string(), "PORT=8080".to_string()],
+            &["PORT=8080".to_string()],
+        ));
+        assert!(!has_expected_env(
+            &["PORT=8080".to_string(), "PORT=3000".to_string()],
+            &["PORT=8080".to_string()],
+        ));
+    }
+
+    #[test]
+    fn changed_label_value_is_not_same_config() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container
+            .config
+            .as_mut()
+            .unwrap()
+            .labels
+            .as_mut()
+            .unwrap()
+            .insert("com.example.team".to_string(), "platform".to_string());
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn missing_managed_label_is_not_same_config() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container
+            .config
+            .as_mut()
+            .unwrap()
+            .labels
+            .as_mut()
+            .unwrap()
+            .remove(MANAGED_LABEL_KEY);
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn changed_host_port_is_not_same_config() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container.host_config.as_mut().unwrap().port_bindings = Some(HashMap::from([(
+            "8080/tcp".to_string(),
+            Some(vec![PortBinding {
+                host_ip: Some("127.0.0.1".to_string()),
+                host_port: Some("3000".to_string()),
+            }]),
+        )]));
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn extra_host_port_is_not_same_config() {
+        let mut expected_container = build_expected_container();
+        let container = build_container(&expected_container);
+        expected_container.host_ports = vec![];
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn changed_bind_is_not_same_config() {
+        let expected_container = build_expected_container();
+        let mut container = build_container(&expected_container);
+        container.host_config.as_mut().unwrap().binds =
+            Some(vec!["/data/openwebui:/app/backend/data".to_string()]);
+
+        assert!(!is_same_config(&container, &expected_container));
+    }
+
+    #[test]
+    fn only_absolute_host_paths_are_bind_mounts() {
+        assert!(is_named_volume("ollama-models"));
+        assert!(is_named_volume("./data"));
+        assert!(!is_named_volume(&std::env::temp_dir().join("data").to_string_lossy()));
+    }
 }
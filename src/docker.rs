@@ -0,0 +1,893 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use bollard::{API_DEFAULT_VERSION, ClientVersion, Docker, auth::DockerCredentials};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    config::{DockerTlsConfig, LlmChatConfig},
+    error::AppError,
+};
+
+/// The timeout, in seconds, to use for requests to the Docker API.
+const DOCKER_CONNECT_TIMEOUT_SECS: u64 = 120;
+
+/// How long to wait between attempts to reach Docker while waiting for it to become available.
+const DOCKER_CONNECT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The Docker host to connect to when no other host is configured.
+#[cfg(unix)]
+const LOCAL_DOCKER_HOST: &'static str = "unix:///var/run/docker.sock";
+
+/// The Docker host to connect to when no other host is configured.
+#[cfg(windows)]
+const LOCAL_DOCKER_HOST: &'static str = "npipe:////./pipe/docker_engine";
+
+/// The server address the Docker CLI stores Docker Hub credentials under.
+const DOCKER_HUB_CREDENTIALS_SERVER: &'static str = "https://index.docker.io/v1/";
+
+/// How long to wait for a credential helper before pulling without its credentials.
+///
+/// Some helpers prompt to unlock a keychain, which would otherwise block the pull indefinitely.
+const CREDENTIAL_HELPER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The username credential helpers return when the secret is an identity token.
+const IDENTITY_TOKEN_USERNAME: &'static str = "<token>";
+
+/// Where the Docker host to connect to was resolved from.
+#[derive(Debug, Clone)]
+enum DockerHostSource {
+    /// An explicit host, from the `DOCKER_HOST` environment variable.
+    Host(String),
+
+    /// A Docker CLI context, from the `DOCKER_CONTEXT` environment variable
+    /// or the current context in the Docker CLI config.
+    Context(String),
+
+    /// The local defaults for the platform.
+    LocalDefaults,
+}
+
+/// The parts of the Docker CLI config (`config.json`) that are used.
+#[derive(Deserialize, Debug, Clone)]
+struct DockerCliConfig {
+    /// The currently selected context.
+    #[serde(rename = "currentContext")]
+    current_context: Option<String>,
+
+    /// The credential helper to use for every registry, if any.
+    #[serde(rename = "credsStore")]
+    creds_store: Option<String>,
+
+    /// The credential helper to use for each registry, keyed by the registry.
+    #[serde(rename = "credHelpers", default)]
+    cred_helpers: HashMap<String, String>,
+
+    /// The credentials stored in the config itself, keyed by the registry.
+    #[serde(rename = "auths", default)]
+    auths: HashMap<String, DockerCliAuth>,
+}
+
+/// Credentials stored in the Docker CLI config for a registry.
+#[derive(Deserialize, Debug, Clone)]
+struct DockerCliAuth {
+    /// The username and password, as base64-encoded `username:password`.
+    #[serde(rename = "auth")]
+    auth: Option<String>,
+
+    /// The identity token for the registry, if one was issued.
+    #[serde(rename = "identitytoken")]
+    identity_token: Option<String>,
+}
+
+/// The credentials returned by a credential helper's `get` command.
+#[derive(Deserialize, Debug, Clone)]
+struct CredentialHelperCredentials {
+    /// The username, or `<token>` if the secret is an identity token.
+    #[serde(rename = "Username")]
+    username: String,
+
+    /// The password or identity token.
+    #[serde(rename = "Secret")]
+    secret: String,
+}
+
+/// The metadata for a Docker CLI context (`contexts/meta/*/meta.json`).
+#[derive(Deserialize, Debug, Clone)]
+struct DockerContextMetadata {
+    /// The name of the context.
+    #[serde(rename = "Name")]
+    name: String,
+
+    /// The endpoints for the context.
+    #[serde(rename = "Endpoints")]
+    endpoints: DockerContextEndpoints,
+}
+
+/// The endpoints for a Docker CLI context.
+#[derive(Deserialize, Debug, Clone)]
+struct DockerContextEndpoints {
+    /// The Docker endpoint.
+    #[serde(rename = "docker")]
+    docker: Option<DockerContextEndpoint>,
+}
+
+/// A Docker endpoint for a Docker CLI context.
+#[derive(Deserialize, Debug, Clone)]
+struct DockerContextEndpoint {
+    /// The host for the endpoint.
+    #[serde(rename = "Host")]
+    host: Option<String>,
+}
+
+/// Connects to Docker (or any Docker-compatible API).
+///
+/// The host to connect to is resolved in this order:
+///
+/// 1. The `docker_tls` config, if set.
+/// 2. The `DOCKER_HOST` environment variable.
+/// 3. The Docker CLI context set in the `DOCKER_CONTEXT` environment variable.
+/// 4. The current Docker CLI context set in the Docker CLI config.
+/// 5. The local defaults for the platform.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+pub fn connect_docker(app_config: &LlmChatConfig) -> Result<Docker, AppError> {
+    let client_version = get_client_version(app_config);
+
+    if let Some(docker_tls) = &app_config.docker_tls {
+        return connect_docker_with_tls(docker_tls, &client_version);
+    }
+
+    let docker_config_dir = get_docker_config_dir();
+
+    let current_context = docker_config_dir
+        .as_ref()
+        .and_then(|config_dir| read_current_docker_context(config_dir));
+
+    let host_source = resolve_docker_host_source(
+        env::var("DOCKER_HOST").ok(),
+        env::var("DOCKER_CONTEXT").ok(),
+        current_context,
+    );
+
+    match host_source {
+        DockerHostSource::Host(host) => connect_docker_with_host(&host, &client_version),
+
+        DockerHostSource::Context(context_name) => {
+            let docker_config_dir = docker_config_dir.ok_or_else(|| {
+                AppError::GenericError(
+                    "Failed to determine the Docker CLI config directory".to_string(),
+                )
+            })?;
+
+            match get_docker_context_host(&docker_config_dir, &context_name)? {
+                Some(host) => connect_docker_with_host(&host, &client_version),
+
+                None => connect_docker_with_host(LOCAL_DOCKER_HOST, &client_version),
+            }
+        }
+
+        DockerHostSource::LocalDefaults => {
+            connect_docker_with_host(LOCAL_DOCKER_HOST, &client_version)
+        }
+    }
+}
+
+/// Parses a Docker API version in the form `major.minor` (e.g. `1.41`).
+///
+/// # Arguments
+///
+/// * `version` - The version to parse.
+pub fn parse_api_version(version: &str) -> Option<ClientVersion> {
+    let (major_version, minor_version) = version.trim().split_once('.')?;
+
+    Some(ClientVersion {
+        major_version: major_version.parse().ok()?,
+        minor_version: minor_version.parse().ok()?,
+    })
+}
+
+/// Gets the Docker API version to use, which is `docker_api_version` if it's set.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+fn get_client_version(app_config: &LlmChatConfig) -> ClientVersion {
+    app_config
+        .docker_api_version
+        .as_deref()
+        .and_then(parse_api_version)
+        .unwrap_or(*API_DEFAULT_VERSION)
+}
+
+/// Whether a Docker error is the engine rejecting the Docker API version the app used,
+/// either for every request or for a specific option.
+///
+/// # Arguments
+///
+/// * `err` - The error from the Docker API.
+pub fn is_api_version_error(err: &bollard::errors::Error) -> bool {
+    match err {
+        bollard::errors::Error::DockerResponseServerError { message, .. } => {
+            message.to_lowercase().contains("api version")
+        }
+
+        _ => false,
+    }
+}
+
+/// Converts an error from the Docker API, giving API version mismatches their own error.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client the request was made with.
+/// * `err` - The error from the Docker API.
+pub fn map_docker_error(docker: &Docker, err: bollard::errors::Error) -> AppError {
+    match err {
+        bollard::errors::Error::DockerResponseServerError { message, .. }
+            if is_api_version_error(&err) =>
+        {
+            AppError::DockerApiVersionMismatch {
+                client_version: docker.client_version().to_string(),
+                detail: message,
+            }
+        }
+
+        err => AppError::DockerError(err),
+    }
+}
+
+/// The Docker API version the app uses, and the versions the Docker engine supports.
+#[derive(Serialize, Debug, Clone)]
+pub struct DockerApiVersion {
+    /// The Docker API version the app uses.
+    #[serde(rename = "clientVersion")]
+    pub client_version: String,
+
+    /// Whether the version was pinned with `docker_api_version`.
+    #[serde(rename = "pinned")]
+    pub pinned: bool,
+
+    /// Whether the engine accepts the version.
+    #[serde(rename = "compatible")]
+    pub compatible: bool,
+
+    /// The version of the Docker engine, if it accepted the version.
+    #[serde(rename = "engineVersion", skip_serializing_if = "Option::is_none")]
+    pub engine_version: Option<String>,
+
+    /// The latest Docker API version the engine supports, if it accepted the version.
+    #[serde(rename = "engineApiVersion", skip_serializing_if = "Option::is_none")]
+    pub engine_api_version: Option<String>,
+
+    /// The oldest Docker API version the engine supports, if it accepted the version.
+    #[serde(rename = "engineMinApiVersion", skip_serializing_if = "Option::is_none")]
+    pub engine_min_api_version: Option<String>,
+
+    /// The engine's error message, if it rejected the version.
+    #[serde(rename = "error", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Gets the Docker API version the app uses, and checks it against the Docker engine.
+///
+/// The engine rejects every request made with a version it doesn't support, so only its
+/// error message is available when the versions don't match.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn get_docker_api_version(
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+) -> Result<DockerApiVersion, AppError> {
+    let mut docker_api_version = DockerApiVersion {
+        client_version: docker.client_version().to_string(),
+        pinned: app_config.docker_api_version.is_some(),
+        compatible: true,
+        engine_version: None,
+        engine_api_version: None,
+        engine_min_api_version: None,
+        error: None,
+    };
+
+    match docker.version().await {
+        Ok(engine_version) => {
+            docker_api_version.engine_version = engine_version.version;
+            docker_api_version.engine_api_version = engine_version.api_version;
+            docker_api_version.engine_min_api_version = engine_version.min_api_version;
+        }
+
+        Err(err) if is_api_version_error(&err) => {
+            docker_api_version.compatible = false;
+            docker_api_version.error = Some(err.to_string());
+        }
+
+        Err(err) => return Err(AppError::DockerError(err)),
+    }
+
+    Ok(docker_api_version)
+}
+
+/// Connects to Docker and waits for it to become available.
+///
+/// This retries until `docker_connect_timeout_secs` elapses, so the app can be launched
+/// before Docker Desktop has finished starting. Remote TLS hosts aren't retried,
+/// so handshake failures are reported right away.
+///
+/// # Arguments
+///
+/// * `app_config` - The application configuration.
+pub async fn connect_docker_with_retry(app_config: &LlmChatConfig) -> Result<Docker, AppError> {
+    let docker = connect_docker(app_config)?;
+
+    if app_config.docker_tls.is_some() {
+        verify_docker_tls_connection(&docker).await?;
+
+        return Ok(docker);
+    }
+
+    let connect_timeout = Duration::from_secs(app_config.docker_connect_timeout_secs);
+    let started_at = Instant::now();
+
+    loop {
+        // A single ping can hang on an unresponsive daemon, so it's bounded by the time left.
+        let time_left = connect_timeout.saturating_sub(started_at.elapsed());
+        let ping_timeout = time_left.max(DOCKER_CONNECT_RETRY_INTERVAL);
+
+        let ping_error = match tokio::time::timeout(ping_timeout, docker.ping()).await {
+            Ok(Ok(_)) => return Ok(docker),
+
+            // Docker is up, it just doesn't accept the version, so there's no point waiting.
+            Ok(Err(err)) if is_api_version_error(&err) => {
+                return Err(map_docker_error(&docker, err));
+            }
+
+            Ok(Err(err)) => err.to_string(),
+            Err(_) => "Timed out waiting for a response".to_string(),
+        };
+
+        if started_at.elapsed() >= connect_timeout {
+            return Err(AppError::DockerUnavailableError(
+                started_at.elapsed().as_secs(),
+                ping_error,
+            ));
+        }
+
+        println!("Docker isn't available yet, retrying: {}", ping_error);
+        tokio::time::sleep(DOCKER_CONNECT_RETRY_INTERVAL).await;
+    }
+}
+
+/// Resolves where the Docker host should come from.
+///
+/// # Arguments
+///
+/// * `docker_host` - The value of the `DOCKER_HOST` environment variable.
+/// * `docker_context` - The value of the `DOCKER_CONTEXT` environment variable.
+/// * `current_context` - The current context in the Docker CLI config.
+fn resolve_docker_host_source(
+    docker_host: Option<String>,
+    docker_context: Option<String>,
+    current_context: Option<String>,
+) -> DockerHostSource {
+    if let Some(docker_host) = docker_host.filter(|host| !host.is_empty()) {
+        return DockerHostSource::Host(docker_host);
+    }
+
+    let context_name = docker_context
+        .filter(|context| !context.is_empty())
+        .or(current_context.filter(|context| !context.is_empty()));
+
+    match context_name {
+        Some(context_name) if context_name != "default" => DockerHostSource::Context(context_name),
+
+        _ => DockerHostSource::LocalDefaults,
+    }
+}
+
+/// Connects to Docker with an explicit host.
+///
+/// # Arguments
+///
+/// * `host` - The host to connect to (e.g. `unix:///var/run/docker.sock` or `tcp://127.0.0.1:2375`).
+/// * `client_version` - The Docker API version to use.
+fn connect_docker_with_host(
+    host: &str,
+    client_version: &ClientVersion,
+) -> Result<Docker, AppError> {
+    let docker = match host {
+        #[cfg(unix)]
+        host if host.starts_with("unix://") => {
+            Docker::connect_with_unix(host, DOCKER_CONNECT_TIMEOUT_SECS, client_version)
+        }
+
+        #[cfg(windows)]
+        host if host.starts_with("npipe://") => {
+            Docker::connect_with_named_pipe(host, DOCKER_CONNECT_TIMEOUT_SECS, client_version)
+        }
+
+        host if host.starts_with("tcp://") || host.starts_with("http://") => {
+            Docker::connect_with_http(host, DOCKER_CONNECT_TIMEOUT_SECS, client_version)
+        }
+
+        _ => {
+            return Err(AppError::GenericError(format!(
+                "Unsupported Docker host: {}",
+                host
+            )));
+        }
+    };
+
+    docker.map_err(|e| AppError::DockerError(e))
+}
+
+/// Connects to a remote Docker host over TLS.
+///
+/// # Arguments
+///
+/// * `docker_tls` - The TLS config for the remote Docker host.
+/// * `client_version` - The Docker API version to use.
+fn connect_docker_with_tls(
+    docker_tls: &DockerTlsConfig,
+    client_version: &ClientVersion,
+) -> Result<Docker, AppError> {
+    let ca_cert_path = Path::new(&docker_tls.ca_cert_path);
+    let client_cert_path = Path::new(&docker_tls.client_cert_path);
+    let client_key_path = Path::new(&docker_tls.client_key_path);
+
+    for cert_file_path in [ca_cert_path, client_cert_path, client_key_path] {
+        if let Err(err) = File::open(cert_file_path) {
+            return Err(AppError::DockerTlsError(format!(
+                "Failed to read '{}': {}",
+                cert_file_path.display(),
+                err
+            )));
+        }
+    }
+
+    Docker::connect_with_ssl(
+        &docker_tls.host,
+        client_key_path,
+        client_cert_path,
+        ca_cert_path,
+        DOCKER_CONNECT_TIMEOUT_SECS,
+        client_version,
+    )
+    .map_err(|e| AppError::DockerTlsError(e.to_string()))
+}
+
+/// Verifies the TLS connection to a remote Docker host.
+///
+/// The TLS handshake only happens on the first request, so this pings
+/// the Docker host to surface any handshake failures.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+pub async fn verify_docker_tls_connection(docker: &Docker) -> Result<(), AppError> {
+    docker.ping().await.map_err(|e| match is_api_version_error(&e) {
+        true => map_docker_error(docker, e),
+
+        false => AppError::DockerTlsError(format!(
+            "Failed to connect to the Docker host over TLS: {}",
+            e
+        )),
+    })?;
+
+    Ok(())
+}
+
+/// Gets the Docker CLI config directory.
+///
+/// This is either the `DOCKER_CONFIG` environment variable or `~/.docker`.
+fn get_docker_config_dir() -> Option<PathBuf> {
+    if let Ok(docker_config_dir) = env::var("DOCKER_CONFIG") {
+        return Some(PathBuf::from(docker_config_dir));
+    }
+
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .map(|home_dir| PathBuf::from(home_dir).join(".docker"))
+}
+
+/// Reads the current context from the Docker CLI config, if one is set.
+///
+/// # Arguments
+///
+/// * `docker_config_dir` - The Docker CLI config directory.
+fn read_current_docker_context(docker_config_dir: &Path) -> Option<String> {
+    let config_file_contents = fs::read_to_string(docker_config_dir.join("config.json")).ok()?;
+
+    serde_json::from_str::<DockerCliConfig>(&config_file_contents)
+        .ok()?
+        .current_context
+}
+
+/// Gets the Docker host for a Docker CLI context.
+///
+/// Returns `None` if the context doesn't define a Docker host.
+///
+/// # Arguments
+///
+/// * `docker_config_dir` - The Docker CLI config directory.
+/// * `context_name` - The name of the context.
+fn get_docker_context_host(
+    docker_config_dir: &Path,
+    context_name: &str,
+) -> Result<Option<String>, AppError> {
+    let contexts_meta_dir = docker_config_dir.join("contexts").join("meta");
+
+    let context_dirs = fs::read_dir(&contexts_meta_dir).map_err(|e| AppError::IOError(e))?;
+
+    for context_dir in context_dirs.flatten() {
+        let Ok(meta_contents) = fs::read_to_string(context_dir.path().join("meta.json")) else {
+            continue;
+        };
+
+        let Ok(context_metadata) = serde_json::from_str::<DockerContextMetadata>(&meta_contents)
+        else {
+            continue;
+        };
+
+        if context_metadata.name == context_name {
+            return Ok(context_metadata
+                .endpoints
+                .docker
+                .and_then(|endpoint| endpoint.host));
+        }
+    }
+
+    Err(AppError::GenericError(format!(
+        "Docker context '{}' was not found",
+        context_name
+    )))
+}
+
+/// Gets the credentials for pulling from a registry from the Docker CLI config.
+///
+/// The credential helper configured for the registry (`credHelpers`) or for every
+/// registry (`credsStore`) is asked first, falling back to the credentials stored in
+/// the config's `auths`. Returns `None` if there aren't any, so the pull is anonymous.
+///
+/// The credential helper is given `CREDENTIAL_HELPER_TIMEOUT` to respond before falling
+/// back to the `auths`.
+///
+/// # Arguments
+///
+/// * `registry` - The registry to get the credentials for (e.g. `docker.io` or `ghcr.io`).
+pub async fn get_registry_credentials(registry: &str) -> Option<DockerCredentials> {
+    let docker_config_dir = get_docker_config_dir()?;
+    let config_file_contents = fs::read_to_string(docker_config_dir.join("config.json")).ok()?;
+    let docker_cli_config = serde_json::from_str::<DockerCliConfig>(&config_file_contents).ok()?;
+
+    let server_address = match registry {
+        "docker.io" => DOCKER_HUB_CREDENTIALS_SERVER,
+        registry => registry,
+    };
+
+    let credential_helper = docker_cli_config
+        .cred_helpers
+        .get(registry)
+        .or_else(|| docker_cli_config.cred_helpers.get(server_address))
+        .or(docker_cli_config.creds_store.as_ref());
+
+    if let Some(credential_helper) = credential_helper {
+        match tokio::time::timeout(
+            CREDENTIAL_HELPER_TIMEOUT,
+            get_credential_helper_credentials(credential_helper, server_address),
+        )
+        .await
+        {
+            Ok(Ok(Some(credentials))) => return Some(credentials),
+
+            Ok(Ok(None)) => {}
+
+            Ok(Err(err)) => println!(
+                "Warning: Failed to get the credentials for '{}' from the '{}' credential helper: {}",
+                registry, credential_helper, err
+            ),
+
+            Err(_) => println!(
+                "Warning: The '{}' credential helper didn't respond within {} seconds, so the credentials for '{}' weren't used",
+                credential_helper,
+                CREDENTIAL_HELPER_TIMEOUT.as_secs(),
+                registry
+            ),
+        }
+    }
+
+    get_stored_credentials(&docker_cli_config, server_address)
+}
+
+/// Gets the credentials for a registry from a Docker credential helper
+/// (e.g. `osxkeychain`, `wincred`, or `pass`).
+///
+/// Returns `None` if the helper doesn't have credentials for the registry.
+///
+/// # Arguments
+///
+/// * `credential_helper` - The name of the helper, without the `docker-credential-` prefix.
+/// * `server_address` - The server address the credentials are stored under.
+async fn get_credential_helper_credentials(
+    credential_helper: &str,
+    server_address: &str,
+) -> Result<Option<DockerCredentials>, AppError> {
+    let program = format!("docker-credential-{}", credential_helper);
+
+    let mut child = tokio::process::Command::new(&program)
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Stops the helper if it times out.
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::GenericError(format!("Failed to run '{}': {}", program, e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(server_address.as_bytes())
+            .await
+            .map_err(|e| AppError::IOError(e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| AppError::IOError(e))?;
+
+    if !output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        // Helpers report that they don't have credentials for the server on stdout.
+        if stdout.contains("credentials not found") {
+            return Ok(None);
+        }
+
+        return Err(AppError::GenericError(format!(
+            "'{}' exited with {}: {}",
+            program,
+            output.status,
+            stdout.trim()
+        )));
+    }
+
+    let helper_credentials = serde_json::from_slice::<CredentialHelperCredentials>(&output.stdout)
+        .map_err(|e| {
+            AppError::GenericError(format!("Failed to parse the output of '{}': {}", program, e))
+        })?;
+
+    Ok(Some(build_docker_credentials(
+        helper_credentials.username,
+        helper_credentials.secret,
+        server_address,
+    )))
+}
+
+/// Gets the credentials for a registry that are stored in the Docker CLI config's `auths`.
+///
+/// # Arguments
+///
+/// * `docker_cli_config` - The Docker CLI config.
+/// * `server_address` - The server address the credentials are stored under.
+fn get_stored_credentials(
+    docker_cli_config: &DockerCliConfig,
+    server_address: &str,
+) -> Option<DockerCredentials> {
+    let server_host = get_server_address_host(server_address);
+
+    let (_, stored_auth) = docker_cli_config.auths.iter().find(|(auth_server_address, _)| {
+        get_server_address_host(auth_server_address) == server_host
+    })?;
+
+    if let Some(identity_token) = &stored_auth.identity_token {
+        return Some(build_docker_credentials(
+            IDENTITY_TOKEN_USERNAME.to_string(),
+            identity_token.clone(),
+            server_address,
+        ));
+    }
+
+    let decoded_auth = BASE64_STANDARD.decode(stored_auth.auth.as_ref()?).ok()?;
+    let decoded_auth = String::from_utf8(decoded_auth).ok()?;
+    let (username, password) = decoded_auth.split_once(':')?;
+
+    Some(build_docker_credentials(
+        username.to_string(),
+        password.to_string(),
+        server_address,
+    ))
+}
+
+/// Builds the credentials to pass to the Docker API.
+///
+/// # Arguments
+///
+/// * `username` - The username, or `<token>` if the secret is an identity token.
+/// * `secret` - The password or identity token.
+/// * `server_address` - The server address the credentials are for.
+fn build_docker_credentials(
+    username: String,
+    secret: String,
+    server_address: &str,
+) -> DockerCredentials {
+    if username == IDENTITY_TOKEN_USERNAME {
+        return DockerCredentials {
+            identitytoken: Some(secret),
+            serveraddress: Some(server_address.to_string()),
+            ..Default::default()
+        };
+    }
+
+    DockerCredentials {
+        username: Some(username),
+        password: Some(secret),
+        serveraddress: Some(server_address.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Gets the host of a server address, since the Docker CLI config stores some
+/// with a scheme and path (e.g. `https://index.docker.io/v1/`).
+///
+/// # Arguments
+///
+/// * `server_address` - The server address.
+fn get_server_address_host(server_address: &str) -> &str {
+    let server_address = server_address
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+
+    server_address.split('/').next().unwrap_or(server_address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docker_host_takes_precedence() {
+        let host_source = resolve_docker_host_source(
+            Some("tcp://127.0.0.1:2375".to_string()),
+            Some("remote".to_string()),
+            Some("desktop-linux".to_string()),
+        );
+
+        assert!(
+            matches!(host_source, DockerHostSource::Host(host) if host == "tcp://127.0.0.1:2375")
+        );
+    }
+
+    #[test]
+    fn docker_context_takes_precedence_over_current_context() {
+        let host_source = resolve_docker_host_source(
+            None,
+            Some("remote".to_string()),
+            Some("desktop-linux".to_string()),
+        );
+
+        assert!(matches!(host_source, DockerHostSource::Context(context) if context == "remote"));
+    }
+
+    #[test]
+    fn current_context_is_used_without_env_vars() {
+        let host_source = resolve_docker_host_source(None, None, Some("desktop-linux".to_string()));
+
+        assert!(
+            matches!(host_source, DockerHostSource::Context(context) if context == "desktop-linux")
+        );
+    }
+
+    #[test]
+    fn local_defaults_are_used_without_a_host_or_context() {
+        let host_source = resolve_docker_host_source(None, None, None);
+
+        assert!(matches!(host_source, DockerHostSource::LocalDefaults));
+    }
+
+    #[test]
+    fn empty_env_values_are_ignored() {
+        let host_source = resolve_docker_host_source(
+            Some(String::new()),
+            Some(String::new()),
+            Some("desktop-linux".to_string()),
+        );
+
+        assert!(
+            matches!(host_source, DockerHostSource::Context(context) if context == "desktop-linux")
+        );
+
+        let host_source = resolve_docker_host_source(
+            Some(String::new()),
+            Some(String::new()),
+            Some(String::new()),
+        );
+
+        assert!(matches!(host_source, DockerHostSource::LocalDefaults));
+    }
+
+    #[test]
+    fn default_context_uses_local_defaults() {
+        let host_source = resolve_docker_host_source(None, Some("default".to_string()), None);
+
+        assert!(matches!(host_source, DockerHostSource::LocalDefaults));
+
+        let host_source = resolve_docker_host_source(None, None, Some("default".to_string()));
+
+        assert!(matches!(host_source, DockerHostSource::LocalDefaults));
+    }
+
+    #[test]
+    fn default_docker_context_overrides_current_context() {
+        let host_source = resolve_docker_host_source(
+            None,
+            Some("default".to_string()),
+            Some("desktop-linux".to_string()),
+        );
+
+        assert!(matches!(host_source, DockerHostSource::LocalDefaults));
+    }
+
+    fn parse_docker_cli_config(json: &str) -> DockerCliConfig {
+        serde_json::from_str::<DockerCliConfig>(json).unwrap()
+    }
+
+    #[test]
+    fn stored_credentials_are_decoded() {
+        // "user:p@ss:word" encoded with base64.
+        let docker_cli_config = parse_docker_cli_config(
+            r#"{ "auths": { "https://index.docker.io/v1/": { "auth": "dXNlcjpwQHNzOndvcmQ=" } } }"#,
+        );
+
+        let credentials =
+            get_stored_credentials(&docker_cli_config, DOCKER_HUB_CREDENTIALS_SERVER).unwrap();
+
+        assert_eq!(credentials.username.as_deref(), Some("user"));
+        assert_eq!(credentials.password.as_deref(), Some("p@ss:word"));
+        assert_eq!(credentials.serveraddress.as_deref(), Some(DOCKER_HUB_CREDENTIALS_SERVER));
+    }
+
+    #[test]
+    fn stored_identity_token_takes_precedence() {
+        let docker_cli_config = parse_docker_cli_config(
+            r#"{ "auths": { "ghcr.io": { "auth": "dXNlcjpwYXNz", "identitytoken": "token" } } }"#,
+        );
+
+        let credentials = get_stored_credentials(&docker_cli_config, "ghcr.io").unwrap();
+
+        assert_eq!(credentials.identitytoken.as_deref(), Some("token"));
+        assert_eq!(credentials.username, None);
+        assert_eq!(credentials.password, None);
+    }
+
+    #[test]
+    fn stored_credentials_match_the_registry_host() {
+        let docker_cli_config = parse_docker_cli_config(
+            r#"{ "auths": { "https://registry.example.com/v2/": { "auth": "dXNlcjpwYXNz" } } }"#,
+        );
+
+        assert!(get_stored_credentials(&docker_cli_config, "registry.example.com").is_some());
+        assert!(get_stored_credentials(&docker_cli_config, "ghcr.io").is_none());
+    }
+
+    #[test]
+    fn no_stored_credentials_without_auth() {
+        let docker_cli_config =
+            parse_docker_cli_config(r#"{ "auths": { "ghcr.io": {} }, "credsStore": "desktop" }"#);
+
+        assert!(get_stored_credentials(&docker_cli_config, "ghcr.io").is_none());
+    }
+}
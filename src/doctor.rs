@@ -0,0 +1,439 @@
+use std::{collections::HashMap, net::TcpListener, path::PathBuf};
+
+use bollard::{Docker, query_parameters::ListContainersOptionsBuilder};
+use futures_util::{FutureExt, TryFutureExt, future::BoxFuture};
+use serde::Serialize;
+
+use crate::{
+    config::LlmChatConfig,
+    container::{self, MANAGED_LABEL_KEY},
+    docker,
+    error::AppError,
+    utils,
+};
+
+/// The context a doctor check runs with.
+#[derive(Clone)]
+pub struct DoctorContext {
+    /// The Docker client.
+    pub docker: Docker,
+
+    /// The config for the app.
+    pub config: LlmChatConfig,
+
+    /// The path to the Open WebUI data directory.
+    pub data_dir: PathBuf,
+
+    /// The port on the host that Open WebUI is bound to.
+    pub host_port: u16,
+}
+
+/// The outcome of diagnosing a problem.
+#[derive(Debug, Clone, Default)]
+pub struct DoctorDiagnosis {
+    /// A description of the problem that was found, if any.
+    pub problem: Option<String>,
+
+    /// Extra output gathered while diagnosing (e.g. from a command run in a container).
+    pub details: Option<String>,
+}
+
+impl From<Option<String>> for DoctorDiagnosis {
+    fn from(problem: Option<String>) -> Self {
+        Self {
+            problem,
+            details: None,
+        }
+    }
+}
+
+/// Diagnoses a problem, returning a description of the problem if one was found.
+type DoctorDiagnoseFn =
+    Box<dyn Fn(DoctorContext) -> BoxFuture<'static, Result<DoctorDiagnosis, AppError>> + Send + Sync>;
+
+/// Attempts to fix a problem, returning a description of what was done.
+type DoctorFixFn =
+    Box<dyn Fn(DoctorContext) -> BoxFuture<'static, Result<String, AppError>> + Send + Sync>;
+
+/// A check for a common problem, with an optional automatic fix.
+pub struct DoctorCheck {
+    /// The name of the check.
+    pub name: &'static str,
+
+    /// Diagnoses the problem.
+    pub diagnose: DoctorDiagnoseFn,
+
+    /// Fixes the problem, if it can be fixed automatically.
+    pub fix: Option<DoctorFixFn>,
+}
+
+/// The result of running a doctor check.
+#[derive(Serialize, Debug, Clone)]
+pub struct DoctorCheckResult {
+    /// The name of the check.
+    #[serde(rename = "name")]
+    pub name: String,
+
+    /// A description of the problem that was found, if any.
+    #[serde(rename = "problem", skip_serializing_if = "Option::is_none")]
+    pub problem: Option<String>,
+
+    /// Extra output gathered while diagnosing, if any.
+    #[serde(rename = "details", skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+
+    /// Whether the problem was fixed.
+    #[serde(rename = "fixed")]
+    pub fixed: bool,
+
+    /// A description of what was done to fix the problem, or why the fix failed.
+    #[serde(rename = "fixDetails", skip_serializing_if = "Option::is_none")]
+    pub fix_details: Option<String>,
+}
+
+/// Gets the checks the doctor runs.
+pub fn get_doctor_checks() -> Vec<DoctorCheck> {
+    vec![
+        DoctorCheck {
+            name: "Docker is running",
+            diagnose: Box::new(|ctx| diagnose_docker_running(ctx).map_ok(DoctorDiagnosis::from).boxed()),
+            fix: None,
+        },
+        DoctorCheck {
+            name: "No stale containers",
+            diagnose: Box::new(|ctx| diagnose_stale_containers(ctx).map_ok(DoctorDiagnosis::from).boxed()),
+            fix: Some(Box::new(|ctx| fix_stale_containers(ctx).boxed())),
+        },
+        DoctorCheck {
+            name: "Data directory ownership",
+            diagnose: Box::new(|ctx| diagnose_data_dir_ownership(ctx).map_ok(DoctorDiagnosis::from).boxed()),
+            fix: Some(Box::new(|ctx| fix_data_dir_ownership(ctx).boxed())),
+        },
+        DoctorCheck {
+            name: "Host port is available",
+            diagnose: Box::new(|ctx| diagnose_host_port(ctx).map_ok(DoctorDiagnosis::from).boxed()),
+            fix: None,
+        },
+        DoctorCheck {
+            name: "GPUs are visible in containers",
+            diagnose: Box::new(|ctx| diagnose_gpu_visibility(ctx).boxed()),
+            fix: None,
+        },
+    ]
+}
+
+/// Runs the doctor checks.
+///
+/// # Arguments
+///
+/// * `ctx` - The context to run the checks with.
+/// * `apply_fixes` - Whether to apply fixes for any problems found.
+pub async fn run_doctor(ctx: DoctorContext, apply_fixes: bool) -> Vec<DoctorCheckResult> {
+    let checks = get_doctor_checks();
+    let mut results = Vec::with_capacity(checks.len());
+
+    for check in checks {
+        let diagnosis = match (check.diagnose)(ctx.clone()).await {
+            Ok(diagnosis) => diagnosis,
+            Err(err) => DoctorDiagnosis::from(Some(format!("The check failed to run: {}", err))),
+        };
+
+        let mut result = DoctorCheckResult {
+            name: check.name.to_string(),
+            problem: diagnosis.problem,
+            details: diagnosis.details,
+            fixed: false,
+            fix_details: None,
+        };
+
+        if let (Some(_), Some(fix), true) = (&result.problem, &check.fix, apply_fixes) {
+            match fix(ctx.clone()).await {
+                Ok(fix_details) => {
+                    result.fixed = true;
+                    result.fix_details = Some(fix_details);
+                }
+
+                Err(err) => result.fix_details = Some(format!("Failed to fix: {}", err)),
+            }
+        }
+
+        results.push(result);
+    }
+
+    results
+}
+
+/// Checks that Docker is reachable.
+async fn diagnose_docker_running(ctx: DoctorContext) -> Result<Option<String>, AppError> {
+    match ctx.docker.ping().await {
+        Ok(_) => Ok(None),
+
+        Err(err) => Ok(Some(format!(
+            "Docker isn't reachable ({}). Make sure Docker Desktop (or your container engine) is installed and running, then restart the app.",
+            err
+        ))),
+    }
+}
+
+/// Gets the names of managed containers that aren't part of the current config.
+async fn get_stale_container_names(ctx: &DoctorContext) -> Result<Vec<String>, AppError> {
+    let expected_names = container::get_managed_resources(&ctx.config).containers;
+
+    let mut container_filters = HashMap::<String, Vec<String>>::new();
+    container_filters.insert(
+        "label".to_string(),
+        vec![format!("{}=true", MANAGED_LABEL_KEY)],
+    );
+
+    let list_containers_opts = ListContainersOptionsBuilder::new()
+        .all(true)
+        .filters(&container_filters)
+        .build();
+
+    let containers = ctx
+        .docker
+        .list_containers(Some(list_containers_opts))
+        .await
+        .map_err(|e| docker::map_docker_error(&ctx.docker, e))?;
+
+    let stale_names = containers
+        .into_iter()
+        .filter_map(|container| container.names?.first().map(|name| name.trim_matches('/').to_string()))
+        .filter(|name| !expected_names.contains(name))
+        .collect();
+
+    Ok(stale_names)
+}
+
+/// Checks for managed containers that aren't part of the current config.
+async fn diagnose_stale_containers(ctx: DoctorContext) -> Result<Option<String>, AppError> {
+    let stale_names = get_stale_container_names(&ctx).await?;
+
+    match stale_names.is_empty() {
+        true => Ok(None),
+
+        false => Ok(Some(format!(
+            "Found containers left over from a previous configuration: {}",
+            stale_names.join(", ")
+        ))),
+    }
+}
+
+/// Removes managed containers that aren't part of the current config.
+async fn fix_stale_containers(ctx: DoctorContext) -> Result<String, AppError> {
+    let stale_names = get_stale_container_names(&ctx).await?;
+
+    for container_name in &stale_names {
+        container::ensure_container_removed(&ctx.docker, container_name).await?;
+    }
+
+    Ok(format!("Removed containers: {}", stale_names.join(", ")))
+}
+
+/// Checks that the data directory is owned by the same user as the app data directory.
+#[cfg(target_os = "linux")]
+async fn diagnose_data_dir_ownership(ctx: DoctorContext) -> Result<Option<String>, AppError> {
+    use std::os::unix::fs::MetadataExt;
+
+    let Some(expected_uid) = get_expected_data_dir_uid(&ctx.data_dir)? else {
+        return Ok(None);
+    };
+
+    let data_dir_uid = std::fs::metadata(&ctx.data_dir)
+        .map_err(|e| AppError::IOError(e))?
+        .uid();
+
+    match data_dir_uid == expected_uid {
+        true => Ok(None),
+
+        false => Ok(Some(format!(
+            "The data directory '{}' is owned by user {}, but should be owned by user {}.",
+            ctx.data_dir.display(),
+            data_dir_uid,
+            expected_uid
+        ))),
+    }
+}
+
+/// Checks that the data directory is owned by the same user as the app data directory.
+///
+/// This is only an issue on Linux, so there is nothing to check on other platforms.
+#[cfg(not(target_os = "linux"))]
+async fn diagnose_data_dir_ownership(_ctx: DoctorContext) -> Result<Option<String>, AppError> {
+    Ok(None)
+}
+
+/// Changes the owner of the data directory to the owner of the app data directory.
+#[cfg(target_os = "linux")]
+async fn fix_data_dir_ownership(ctx: DoctorContext) -> Result<String, AppError> {
+    use std::os::unix::fs::MetadataExt;
+
+    let Some(expected_uid) = get_expected_data_dir_uid(&ctx.data_dir)? else {
+        return Ok("Nothing to fix".to_string());
+    };
+
+    let parent_gid = std::fs::metadata(ctx.data_dir.parent().unwrap_or(&ctx.data_dir))
+        .map_err(|e| AppError::IOError(e))?
+        .gid();
+
+    chown_recursive(&ctx.data_dir, expected_uid, parent_gid)?;
+
+    Ok(format!(
+        "Changed the owner of '{}' to user {}",
+        ctx.data_dir.display(),
+        expected_uid
+    ))
+}
+
+/// Changes the owner of the data directory to the owner of the app data directory.
+#[cfg(not(target_os = "linux"))]
+async fn fix_data_dir_ownership(_ctx: DoctorContext) -> Result<String, AppError> {
+    Ok("Nothing to fix".to_string())
+}
+
+/// Gets the user ID that should own the data directory, which is the owner of its parent.
+///
+/// # Arguments
+///
+/// * `data_dir` - The path to the data directory.
+#[cfg(target_os = "linux")]
+fn get_expected_data_dir_uid(data_dir: &PathBuf) -> Result<Option<u32>, AppError> {
+    use std::os::unix::fs::MetadataExt;
+
+    if !data_dir.exists() {
+        return Ok(None);
+    }
+
+    let Some(parent_dir) = data_dir.parent() else {
+        return Ok(None);
+    };
+
+    let parent_uid = std::fs::metadata(parent_dir)
+        .map_err(|e| AppError::IOError(e))?
+        .uid();
+
+    Ok(Some(parent_uid))
+}
+
+/// Recursively changes the owner of a path.
+///
+/// # Arguments
+///
+/// * `path` - The path to change the owner of.
+/// * `uid` - The user ID to set.
+/// * `gid` - The group ID to set.
+#[cfg(target_os = "linux")]
+fn chown_recursive(path: &PathBuf, uid: u32, gid: u32) -> Result<(), AppError> {
+    std::os::unix::fs::chown(path, Some(uid), Some(gid)).map_err(|e| AppError::IOError(e))?;
+
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path).map_err(|e| AppError::IOError(e))? {
+            let entry = entry.map_err(|e| AppError::IOError(e))?;
+
+            chown_recursive(&entry.path(), uid, gid)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the host port is either free or in use by Open WebUI.
+async fn diagnose_host_port(ctx: DoctorContext) -> Result<Option<String>, AppError> {
+    if utils::is_host_port_available(ctx.host_port) {
+        return Ok(None);
+    }
+
+    let container_statuses = container::get_container_statuses(&ctx.docker).await?;
+
+    let openwebui_running = container_statuses.iter().any(|status| {
+        status.name == "local_llm_openwebui" && status.state.as_deref() == Some("running")
+    });
+
+    if openwebui_running {
+        return Ok(None);
+    }
+
+    let suggested_port = TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| AppError::IOError(e))?;
+
+    Ok(Some(format!(
+        "Port {} is in use by another application. Port {} is currently free and could be used instead.",
+        ctx.host_port, suggested_port
+    )))
+}
+
+/// The `nvidia-smi` command to run in a container to report the GPUs it can see.
+const NVIDIA_SMI_QUERY_CMD: [&str; 3] = [
+    "nvidia-smi",
+    "--query-gpu=index,name,memory.total,memory.used,compute_cap,driver_version",
+    "--format=csv",
+];
+
+/// Checks that the GPUs in the config are visible inside the containers they're given to.
+///
+/// This runs `nvidia-smi` in each container with a GPU config and includes its output
+/// in the details. Only NVIDIA GPUs can be checked.
+async fn diagnose_gpu_visibility(ctx: DoctorContext) -> Result<DoctorDiagnosis, AppError> {
+    let mut gpu_containers = Vec::new();
+
+    if let Some(gpu) = &ctx.config.gpu {
+        gpu_containers.push((container::get_container_name("openwebui"), gpu.clone()));
+    }
+
+    for extra_service in ctx.config.get_enabled_extra_services() {
+        if let Some(gpu) = extra_service.gpu {
+            gpu_containers.push((container::get_container_name(&extra_service.name), gpu));
+        }
+    }
+
+    if gpu_containers.is_empty() {
+        return Ok(DoctorDiagnosis {
+            problem: None,
+            details: Some("No containers are configured to use GPUs".to_string()),
+        });
+    }
+
+    let mut problems = Vec::new();
+    let mut details = Vec::new();
+
+    for (container_name, gpu) in gpu_containers {
+        if gpu.driver.as_deref().is_some_and(|driver| driver != "nvidia") {
+            details.push(format!(
+                "{}: Skipped, since only NVIDIA GPUs can be checked",
+                container_name
+            ));
+
+            continue;
+        }
+
+        let cmd = NVIDIA_SMI_QUERY_CMD.iter().map(|arg| arg.to_string()).collect();
+
+        match container::exec_in_container(&ctx.docker, &container_name, cmd).await {
+            Ok(exec_result) if exec_result.exit_code == Some(0) => {
+                details.push(format!("{}:\n{}", container_name, exec_result.stdout.trim_end()));
+            }
+
+            Ok(exec_result) => {
+                let output = format!("{}{}", exec_result.stdout, exec_result.stderr);
+
+                problems.push(format!(
+                    "The GPU isn't visible in '{}'. Make sure the NVIDIA driver and the NVIDIA Container Toolkit are installed on the host, then recreate the container.",
+                    container_name
+                ));
+                details.push(format!("{}:\n{}", container_name, output.trim_end()));
+            }
+
+            Err(err) => problems.push(format!(
+                "Couldn't check the GPU in '{}' (is it running?): {}",
+                container_name, err
+            )),
+        }
+    }
+
+    Ok(DoctorDiagnosis {
+        problem: (!problems.is_empty()).then(|| problems.join("\n")),
+        details: (!details.is_empty()).then(|| details.join("\n\n")),
+    })
+}
@@ -1,7 +1,10 @@
 use std::env::VarError;
 
+use serde::Serialize;
 use thiserror::Error;
 
+use crate::{config::ConfigValidationError, container::ExtraServicesSummary};
+
 /// Errors for the LocalLLM Chat app.
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -17,6 +20,10 @@ pub enum AppError {
     #[error("An I/O operation failed: {0}")]
     IOError(std::io::Error),
 
+    /// The local appdata directory couldn't be resolved.
+    #[error("Failed to resolve the local app data directory ({0}): {1}")]
+    AppDataDirUnresolvedError(String, String),
+
     /// An error occurred with Tauri.
     #[error("An error occurred with tauri: {0}")]
     TauriError(tauri::Error),
@@ -25,7 +32,213 @@ pub enum AppError {
     #[error("Docker error: {0}")]
     DockerError(bollard::errors::Error),
 
+    /// Failed to pull a container image.
+    #[error("Failed to pull image '{image}'{}: {detail}", format_pull_layer(.layer))]
+    ImagePullFailed {
+        /// The image that failed to pull.
+        image: String,
+
+        /// The layer that was being pulled when it failed, if known.
+        layer: Option<String>,
+
+        /// The detailed error message.
+        detail: String,
+    },
+
+    /// A pull was cancelled with the `cancel_pull` command.
+    #[error("The pull of '{0}' was cancelled")]
+    ImagePullCancelled(String),
+
+    /// Failed to load container images from an archive.
+    #[error("Failed to load images from '{path}': {detail}")]
+    ImageLoadFailed {
+        /// The path to the archive.
+        path: String,
+
+        /// The detailed error message.
+        detail: String,
+    },
+
+    /// Docker didn't become available before the connect timeout.
+    #[error("Docker didn't become available after {0} seconds. Make sure Docker is running. ({1})")]
+    DockerUnavailableError(u64, String),
+
+    /// The container engine responded, but couldn't run a container.
+    #[error("The container engine couldn't run a test container: {0}")]
+    EngineSelfTestFailed(String),
+
+    /// The Docker engine doesn't support the Docker API version the app uses.
+    #[error("The Docker engine doesn't support Docker API version {client_version}: {detail}\n\nUpdate Docker, or set 'docker_api_version' in the config to a version the engine supports.")]
+    DockerApiVersionMismatch {
+        /// The Docker API version the app used.
+        client_version: String,
+
+        /// The error message from the Docker engine.
+        detail: String,
+    },
+
+    /// Failed to connect to a remote Docker host over TLS.
+    #[error("Docker TLS error: {0}")]
+    DockerTlsError(String),
+
+    /// Backing up or restoring the data directory failed, or was cancelled.
+    #[error("Backup error: {0}")]
+    BackupError(String),
+
     /// An error occurred while serializing/deserializing YAML.
     #[error("YAML error: {0}")]
-    YamlError(serde_yaml::Error)
+    YamlError(serde_yaml::Error),
+
+    /// The config couldn't be loaded or updated.
+    #[error("Config error: {0}")]
+    ConfigError(String),
+
+    /// The config failed validation.
+    #[error("Invalid config:\n{}", format_validation_errors(.0))]
+    InvalidConfigError(Vec<ConfigValidationError>),
+
+    /// The init command failed in the Open WebUI container.
+    #[error("The init command failed: {0}")]
+    InitCommandError(String),
+
+    /// One or more extra backend services failed to start.
+    #[error("Failed to start extra services: {0}")]
+    ExtraServicesFailed(ExtraServicesSummary)
+}
+
+/// Formats the layer a pull failed on, if it's known.
+///
+/// # Arguments
+///
+/// * `layer` - The layer that was being pulled.
+fn format_pull_layer(layer: &Option<String>) -> String {
+    match layer {
+        Some(layer) => format!(" (layer {})", layer),
+        None => String::new(),
+    }
+}
+
+/// Formats a list of config validation errors, one per line.
+///
+/// # Arguments
+///
+/// * `validation_errors` - The validation errors to format.
+fn format_validation_errors(validation_errors: &[ConfigValidationError]) -> String {
+    validation_errors
+        .iter()
+        .map(|validation_error| format!("- {}", validation_error))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The category of an error, so the frontend can decide how to present it.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppErrorCategory {
+    /// Docker is unavailable or a Docker API call failed.
+    #[serde(rename = "docker")]
+    Docker,
+
+    /// A container image couldn't be pulled or loaded.
+    #[serde(rename = "image")]
+    Image,
+
+    /// The config couldn't be loaded or is invalid.
+    #[serde(rename = "config")]
+    Config,
+
+    /// The local appdata directory couldn't be set up.
+    #[serde(rename = "appData")]
+    AppData,
+
+    /// A container failed to start or initialize.
+    #[serde(rename = "container")]
+    Container,
+
+    /// Any other error.
+    #[serde(rename = "other")]
+    Other,
+}
+
+impl AppError {
+    /// Gets the category of the error.
+    pub fn category(&self) -> AppErrorCategory {
+        match self {
+            AppError::DockerError(_)
+            | AppError::DockerUnavailableError(_, _)
+            | AppError::DockerApiVersionMismatch { .. }
+            | AppError::DockerTlsError(_)
+            | AppError::EngineSelfTestFailed(_) => AppErrorCategory::Docker,
+
+            AppError::ImagePullFailed { .. }
+            | AppError::ImagePullCancelled(_)
+            | AppError::ImageLoadFailed { .. } => AppErrorCategory::Image,
+
+            AppError::YamlError(_) | AppError::ConfigError(_) | AppError::InvalidConfigError(_) => {
+                AppErrorCategory::Config
+            }
+
+            AppError::IOError(_) | AppError::AppDataDirUnresolvedError(_, _) => {
+                AppErrorCategory::AppData
+            }
+
+            AppError::InitCommandError(_) | AppError::ExtraServicesFailed(_) => {
+                AppErrorCategory::Container
+            }
+
+            _ => AppErrorCategory::Other,
+        }
+    }
+
+    /// Whether retrying the failed operation might succeed without changing anything,
+    /// like when Docker wasn't running yet or the network dropped during a pull.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AppError::DockerError(_)
+                | AppError::DockerUnavailableError(_, _)
+                | AppError::ImagePullFailed { .. }
+                | AppError::ImagePullCancelled(_)
+                | AppError::ImageLoadFailed { .. }
+                | AppError::IOError(_)
+        )
+    }
+
+    /// Whether the error is transient, so setup is retried when it fails with it.
+    ///
+    /// This is narrower than `is_retryable`, since a cancelled pull or an image that
+    /// doesn't exist won't succeed by retrying straight away.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AppError::ImagePullCancelled(_) => false,
+
+            AppError::ImagePullFailed { detail, .. } => {
+                let detail = detail.to_lowercase();
+
+                ![
+                    "not found",
+                    "manifest unknown",
+                    "pull access denied",
+                    "unauthorized",
+                ]
+                .iter()
+                .any(|pattern| detail.contains(pattern))
+            }
+
+            AppError::DockerError(bollard::errors::Error::DockerResponseServerError {
+                status_code,
+                ..
+            }) => !matches!(status_code, 400 | 404),
+
+            err => err.is_retryable(),
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
 }
@@ -0,0 +1,485 @@
+use serde::Serialize;
+
+use crate::error::AppErrorCategory;
+
+/// The event emitted while a container image is being pulled.
+pub const PULL_PROGRESS_EVENT: &'static str = "pull-progress";
+
+/// The payload for the `pull-progress` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct PullProgress {
+    /// The image being pulled.
+    #[serde(rename = "image")]
+    pub image: String,
+
+    /// The layer the progress update is for, if any.
+    #[serde(rename = "layerId", skip_serializing_if = "Option::is_none")]
+    pub layer_id: Option<String>,
+
+    /// The status message from the pull.
+    #[serde(rename = "status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// The number of bytes downloaded across all layers of the image.
+    #[serde(rename = "downloadedBytes")]
+    pub downloaded_bytes: i64,
+
+    /// The total number of bytes across all layers of the image that have reported a size.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: i64,
+
+    /// The number of layers that haven't reported a size yet.
+    #[serde(rename = "indeterminateLayers")]
+    pub indeterminate_layers: usize,
+}
+
+/// The event emitted while the required images are pulled, with the progress across all of them.
+pub const TOTAL_PULL_PROGRESS_EVENT: &'static str = "total-pull-progress";
+
+/// The payload for the `total-pull-progress` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct TotalPullProgress {
+    /// The number of bytes downloaded across all of the images.
+    #[serde(rename = "downloadedBytes")]
+    pub downloaded_bytes: i64,
+
+    /// The total number of bytes across all layers that have reported a size.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: i64,
+
+    /// The number of images that have started pulling.
+    #[serde(rename = "imagesStarted")]
+    pub images_started: usize,
+
+    /// The number of images being pulled.
+    #[serde(rename = "imagesTotal")]
+    pub images_total: usize,
+
+    /// Whether every image has started and every layer has reported a size, so `totalBytes`
+    /// won't grow. When it's `false`, show the `pull-progress` of each image instead.
+    #[serde(rename = "isTotalKnown")]
+    pub is_total_known: bool,
+}
+
+/// The event emitted when image pulls are cancelled with the `cancel_pull` command.
+pub const PULL_CANCELLED_EVENT: &'static str = "pull-cancelled";
+
+/// The payload for the `pull-cancelled` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct PullCancelled {
+    /// The images whose pulls were cancelled.
+    #[serde(rename = "images")]
+    pub images: Vec<String>,
+}
+
+/// The event emitted at startup with the `notice` from the config, if it has one.
+pub const NOTICE_EVENT: &'static str = "notice";
+
+/// The event emitted while images are loaded from an archive.
+pub const IMAGE_LOAD_PROGRESS_EVENT: &'static str = "image-load-progress";
+
+/// The payload for the `image-load-progress` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct ImageLoadProgress {
+    /// The path to the archive.
+    #[serde(rename = "path")]
+    pub path: String,
+
+    /// The number of bytes of the archive sent to Docker so far.
+    #[serde(rename = "bytesLoaded")]
+    pub bytes_loaded: u64,
+
+    /// The size of the archive, in bytes.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+}
+
+/// The event emitted at startup with details about the build of the app.
+pub const APP_INFO_EVENT: &'static str = "app-info";
+
+/// Details about the build of the app, for correlating bug reports with a specific build.
+#[derive(Serialize, Debug, Clone)]
+pub struct AppInfo {
+    /// The version of the app.
+    #[serde(rename = "version")]
+    pub version: String,
+
+    /// The git commit the app was built from, or `unknown`.
+    #[serde(rename = "gitCommit")]
+    pub git_commit: String,
+
+    /// The build profile (`debug` or `release`).
+    #[serde(rename = "buildProfile")]
+    pub build_profile: String,
+
+    /// The operating system the app was built for.
+    #[serde(rename = "targetOs")]
+    pub target_os: String,
+
+    /// The CPU architecture the app was built for.
+    #[serde(rename = "targetArch")]
+    pub target_arch: String,
+
+    /// The version of Tauri the app was built with.
+    #[serde(rename = "tauriVersion")]
+    pub tauri_version: String,
+}
+
+impl AppInfo {
+    /// Gets the details about the build of the running app.
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: env!("LOCALLM_GIT_COMMIT").to_string(),
+            build_profile: match cfg!(debug_assertions) {
+                true => "debug".to_string(),
+                false => "release".to_string(),
+            },
+            target_os: std::env::consts::OS.to_string(),
+            target_arch: std::env::consts::ARCH.to_string(),
+            tauri_version: tauri::VERSION.to_string(),
+        }
+    }
+}
+
+/// The event emitted when setup moves on to a new phase.
+pub const SETUP_PHASE_EVENT: &'static str = "setup-phase";
+
+/// A phase of the app's setup.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupProgressPhase {
+    /// The container images are being pulled.
+    #[serde(rename = "pullingImages")]
+    PullingImages,
+
+    /// The containers from a previous session are being reconciled with the config.
+    #[serde(rename = "reconcilingInfrastructure")]
+    ReconcilingInfrastructure,
+
+    /// The networks and containers are being created.
+    #[serde(rename = "creatingInfrastructure")]
+    CreatingInfrastructure,
+
+    /// The init command is running.
+    #[serde(rename = "runningInitCommand")]
+    RunningInitCommand,
+
+    /// Waiting for Open WebUI to become healthy.
+    #[serde(rename = "waitingForOpenWebUi")]
+    WaitingForOpenWebUi,
+
+    /// Setup has finished.
+    #[serde(rename = "ready")]
+    Ready,
+}
+
+/// The payload for the `setup-phase` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct SetupPhaseChanged {
+    /// The phase that started.
+    #[serde(rename = "phase")]
+    pub phase: SetupProgressPhase,
+}
+
+/// The event emitted when setup logs a message.
+pub const SETUP_LOG_EVENT: &'static str = "setup-log";
+
+/// The payload for the `setup-log` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct SetupLog {
+    /// The message that was logged.
+    #[serde(rename = "message")]
+    pub message: String,
+}
+
+/// The event emitted when setup fails.
+pub const SETUP_ERROR_EVENT: &'static str = "setup-error";
+
+/// The payload for the `setup-error` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct SetupError {
+    /// The category of the error.
+    #[serde(rename = "category")]
+    pub category: AppErrorCategory,
+
+    /// The error message.
+    #[serde(rename = "message")]
+    pub message: String,
+
+    /// Whether retrying setup might succeed without changing anything.
+    #[serde(rename = "retryable")]
+    pub retryable: bool,
+}
+
+/// The event emitted when setup failed with a transient error and is being retried.
+pub const SETUP_RETRY_EVENT: &'static str = "setup-retry";
+
+/// The payload for the `setup-retry` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct SetupRetry {
+    /// The attempt that's starting, counting from 1.
+    #[serde(rename = "attempt")]
+    pub attempt: u32,
+
+    /// The maximum number of attempts, including the first.
+    #[serde(rename = "maxAttempts")]
+    pub max_attempts: u32,
+
+    /// The message of the error that failed the previous attempt.
+    #[serde(rename = "message")]
+    pub message: String,
+}
+
+/// The event emitted after each attempt at checking whether Open WebUI is healthy.
+pub const HEALTH_CHECK_EVENT: &'static str = "health-check";
+
+/// The payload for the `health-check` event.
+///
+/// This separates the container's port not being reachable yet from Open WebUI still
+/// starting up behind a reachable port.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct HealthCheckAttempt {
+    /// The number of the attempt, starting at 0.
+    #[serde(rename = "attempt")]
+    pub attempt: u64,
+
+    /// The endpoint that was checked last, if the port was reachable.
+    #[serde(rename = "endpoint", skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Whether a TCP connection to the port could be made.
+    #[serde(rename = "tcpConnected")]
+    pub tcp_connected: bool,
+
+    /// Whether the endpoint responded to the HTTP request.
+    #[serde(rename = "httpResponded")]
+    pub http_responded: bool,
+
+    /// The HTTP status code of the response, if there was one.
+    #[serde(rename = "statusCode", skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+
+    /// Whether the response body was a health status.
+    #[serde(rename = "jsonParsed")]
+    pub json_parsed: bool,
+
+    /// Whether Open WebUI is healthy.
+    #[serde(rename = "healthy")]
+    pub healthy: bool,
+}
+
+/// The event emitted as each of the `preload_models` is preloaded in Ollama.
+pub const MODEL_PRELOAD_EVENT: &'static str = "model-preload";
+
+/// The stage a model being preloaded is in.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelPreloadStatus {
+    /// The model is being pulled.
+    #[serde(rename = "pulling")]
+    Pulling,
+
+    /// The model is being loaded into memory.
+    #[serde(rename = "loading")]
+    Loading,
+
+    /// The model is loaded and ready.
+    #[serde(rename = "ready")]
+    Ready,
+
+    /// The model couldn't be preloaded.
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+/// The payload for the `model-preload` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct ModelPreloadProgress {
+    /// The name of the model.
+    #[serde(rename = "model")]
+    pub model: String,
+
+    /// The stage the model is in.
+    #[serde(rename = "status")]
+    pub status: ModelPreloadStatus,
+
+    /// Why the model couldn't be preloaded, if it failed.
+    #[serde(rename = "error", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The event emitted once all of the `prewarm_images` have been pulled (or failed to).
+pub const PREWARM_IMAGES_COMPLETE_EVENT: &'static str = "prewarm-images-complete";
+
+/// An image that couldn't be pre-warmed.
+#[derive(Serialize, Debug, Clone)]
+pub struct ImagePrewarmFailure {
+    /// The image that failed to pull.
+    #[serde(rename = "image")]
+    pub image: String,
+
+    /// Why the image couldn't be pulled.
+    #[serde(rename = "error")]
+    pub error: String,
+}
+
+/// The payload for the `prewarm-images-complete` event.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PrewarmImagesComplete {
+    /// The images that were pulled.
+    #[serde(rename = "pulledImages")]
+    pub pulled_images: Vec<String>,
+
+    /// The images that were skipped, since they were already present.
+    #[serde(rename = "cachedImages")]
+    pub cached_images: Vec<String>,
+
+    /// The images that couldn't be pulled.
+    #[serde(rename = "failedImages")]
+    pub failed_images: Vec<ImagePrewarmFailure>,
+}
+
+/// The event emitted when Open WebUI has finished loading in the main window.
+pub const WEBUI_LOADED_EVENT: &'static str = "webui-loaded";
+
+/// The payload for the `webui-loaded` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct WebUiLoaded {
+    /// The URL that finished loading.
+    #[serde(rename = "url")]
+    pub url: String,
+}
+
+/// The event emitted when Open WebUI hasn't loaded in the main window within the timeout,
+/// even though the health check passed.
+pub const WEBUI_LOAD_TIMEOUT_EVENT: &'static str = "webui-load-timeout";
+
+/// The payload for the `webui-load-timeout` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct WebUiLoadTimeout {
+    /// The URL that didn't load.
+    #[serde(rename = "url")]
+    pub url: String,
+
+    /// The number of seconds that were waited.
+    #[serde(rename = "timeoutSecs")]
+    pub timeout_secs: u64,
+
+    /// A suggestion for what might be wrong.
+    #[serde(rename = "message")]
+    pub message: String,
+}
+
+/// The event emitted once the networks and containers are set up,
+/// with an `InfrastructureSummary` of what was created, reused, or recreated.
+pub const INFRASTRUCTURE_SUMMARY_EVENT: &'static str = "infrastructure-summary";
+
+/// The event emitted for each network and container after the infrastructure is set up or
+/// reconciled, with the `ResourceOutcome` of what happened to it.
+pub const RESOURCE_OUTCOME_EVENT: &'static str = "resource-outcome";
+
+/// The event emitted when a newer Open WebUI image is available for the configured tag.
+pub const UPDATE_AVAILABLE_EVENT: &'static str = "update-available";
+
+/// The payload for the `update-available` event.
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdateAvailable {
+    /// The image name and tag.
+    #[serde(rename = "image")]
+    pub image: String,
+
+    /// The digest of the local image, if it has one.
+    #[serde(rename = "localDigest", skip_serializing_if = "Option::is_none")]
+    pub local_digest: Option<String>,
+
+    /// The digest of the image in the registry.
+    #[serde(rename = "remoteDigest")]
+    pub remote_digest: String,
+}
+
+/// The event emitted while the data directory is being backed up.
+pub const BACKUP_PROGRESS_EVENT: &'static str = "backup-progress";
+
+/// The event emitted while the data directory is being restored from a backup.
+pub const RESTORE_PROGRESS_EVENT: &'static str = "restore-progress";
+
+/// The payload for the `backup-progress` and `restore-progress` events.
+#[derive(Serialize, Debug, Clone)]
+pub struct DataArchiveProgress {
+    /// The number of bytes archived or extracted so far.
+    #[serde(rename = "bytesProcessed")]
+    pub bytes_processed: u64,
+
+    /// The total number of bytes to archive or extract.
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+}
+
+/// A setup event that was emitted, as recorded in the setup history.
+#[derive(Serialize, Debug, Clone)]
+pub struct AppEvent {
+    /// When the event was emitted, in milliseconds since the Unix epoch.
+    #[serde(rename = "timestampMs")]
+    pub timestamp_ms: u64,
+
+    /// The event and its payload.
+    #[serde(flatten)]
+    pub kind: AppEventKind,
+}
+
+/// The setup events that are recorded in the setup history.
+///
+/// Each variant is named after the event it was emitted as.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "event", content = "payload")]
+pub enum AppEventKind {
+    /// The `setup-phase` event.
+    #[serde(rename = "setup-phase")]
+    SetupPhase(SetupPhaseChanged),
+
+    /// The `pull-progress` event.
+    #[serde(rename = "pull-progress")]
+    PullProgress(PullProgress),
+
+    /// The `total-pull-progress` event.
+    #[serde(rename = "total-pull-progress")]
+    TotalPullProgress(TotalPullProgress),
+
+    /// The `image-load-progress` event.
+    #[serde(rename = "image-load-progress")]
+    ImageLoadProgress(ImageLoadProgress),
+
+    /// The `health-check` event.
+    #[serde(rename = "health-check")]
+    HealthCheck(HealthCheckAttempt),
+
+    /// The `setup-log` event.
+    #[serde(rename = "setup-log")]
+    SetupLog(SetupLog),
+
+    /// The `setup-error` event.
+    #[serde(rename = "setup-error")]
+    SetupError(SetupError),
+
+    /// The `setup-retry` event.
+    #[serde(rename = "setup-retry")]
+    SetupRetry(SetupRetry),
+}
+
+impl AppEventKind {
+    /// Gets the key of a progress update, so a newer update for the same thing
+    /// can replace it in the setup history.
+    ///
+    /// Returns `None` for events that aren't progress updates.
+    pub fn progress_key(&self) -> Option<String> {
+        match self {
+            AppEventKind::PullProgress(progress) => {
+                Some(format!("{}:{}", PULL_PROGRESS_EVENT, progress.image))
+            }
+            AppEventKind::TotalPullProgress(_) => Some(TOTAL_PULL_PROGRESS_EVENT.to_string()),
+            AppEventKind::ImageLoadProgress(progress) => {
+                Some(format!("{}:{}", IMAGE_LOAD_PROGRESS_EVENT, progress.path))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, async_runtime::spawn};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{error::AppError, utils};
+
+/// The name of the lock file in the local appdata directory.
+const INSTANCE_LOCK_FILE_NAME: &'static str = "instance.lock";
+
+/// The message sent to the running instance to ask it to focus its window.
+const FOCUS_REQUEST: &'static str = "focus";
+
+/// The message sent to the running instance to check that it's running, without focusing it.
+const PING_REQUEST: &'static str = "ping";
+
+/// The reply from the running instance once it has handled a request.
+const FOCUS_ACKNOWLEDGEMENT: &'static str = "ok";
+
+/// How long to wait for the running instance to acknowledge a request.
+const FOCUS_REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait for another instance to send its request once it has connected.
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait before accepting connections again after accepting one failed.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The contents of the lock file, identifying the running instance.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct InstanceLock {
+    /// The process ID of the running instance.
+    #[serde(rename = "pid")]
+    pid: u32,
+
+    /// The local port the running instance listens on for focus requests.
+    #[serde(rename = "port")]
+    port: u16,
+}
+
+/// The result of trying to become the only running instance of the app.
+pub enum InstanceLockOutcome {
+    /// This is the only running instance.
+    Acquired,
+
+    /// Another instance is already running, and was asked to focus its window.
+    AlreadyRunning(u32),
+}
+
+/// Tries to become the only running instance of the app.
+///
+/// If another instance is running, it's asked to focus its window. A lock file left
+/// behind by a crashed instance is detected by the recorded instance not responding,
+/// which also covers its PID being reused by an unrelated process.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub async fn acquire_instance_lock(app: &AppHandle) -> Result<InstanceLockOutcome, AppError> {
+    let lock_path = get_instance_lock_path(app)?;
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| AppError::IOError(e))?;
+
+    let instance_lock = InstanceLock {
+        pid: std::process::id(),
+        port: listener
+            .local_addr()
+            .map_err(|e| AppError::IOError(e))?
+            .port(),
+    };
+
+    // The lock file is only replaced once, so two launches racing over a stale
+    // lock file can't both end up thinking they own it.
+    for attempt in 0..2 {
+        match write_instance_lock(&lock_path, &instance_lock) {
+            Ok(_) => {
+                spawn(listen_for_focus_requests(app.clone(), listener));
+
+                return Ok(InstanceLockOutcome::Acquired);
+            }
+
+            Err(err) if err.kind() == ErrorKind::AlreadyExists && attempt == 0 => {
+                let existing_lock = fs::read_to_string(&lock_path)
+                    .ok()
+                    .and_then(|contents| serde_json::from_str::<InstanceLock>(&contents).ok());
+
+                if let Some(existing_lock) = &existing_lock {
+                    if send_request(existing_lock.port, FOCUS_REQUEST).await {
+                        return Ok(InstanceLockOutcome::AlreadyRunning(existing_lock.pid));
+                    }
+
+                    println!(
+                        "Removing the stale instance lock left by PID {}",
+                        existing_lock.pid
+                    );
+                }
+
+                match fs::remove_file(&lock_path) {
+                    Ok(_) => {}
+                    Err(err) if err.kind() == ErrorKind::NotFound => {}
+                    Err(err) => return Err(AppError::IOError(err)),
+                }
+            }
+
+            Err(err) => return Err(AppError::IOError(err)),
+        }
+    }
+
+    Err(AppError::GenericError(
+        "Another instance of the app is starting at the same time".to_string(),
+    ))
+}
+
+/// Gets the PID of the running instance of the app, if there is one.
+///
+/// Unlike `acquire_instance_lock`, this doesn't need an app handle, doesn't focus the
+/// running instance's window, and doesn't create or remove the lock file.
+///
+/// # Arguments
+///
+/// * `app_data_dir` - The local appdata directory for the app.
+pub async fn get_running_instance_pid(app_data_dir: &Path) -> Option<u32> {
+    let existing_lock = fs::read_to_string(app_data_dir.join(INSTANCE_LOCK_FILE_NAME))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<InstanceLock>(&contents).ok())?;
+
+    // A stale lock file is left behind by a crashed instance, which won't respond.
+    match send_request(existing_lock.port, PING_REQUEST).await {
+        true => Some(existing_lock.pid),
+        false => None,
+    }
+}
+
+/// Removes the lock file, if it's owned by this instance.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn release_instance_lock(app: &AppHandle) {
+    let Ok(lock_path) = get_instance_lock_path(app) else {
+        return;
+    };
+
+    let is_owned = fs::read_to_string(&lock_path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<InstanceLock>(&contents).ok())
+        .is_some_and(|instance_lock| instance_lock.pid == std::process::id());
+
+    if is_owned {
+        let _ = fs::remove_file(&lock_path);
+    }
+}
+
+/// Gets the path to the lock file.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+fn get_instance_lock_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = utils::get_app_local_data_dir(app)?;
+
+    Ok(app_data_dir.join(INSTANCE_LOCK_FILE_NAME))
+}
+
+/// Creates the lock file, failing if it already exists.
+///
+/// # Arguments
+///
+/// * `lock_path` - The path to the lock file.
+/// * `instance_lock` - The details of this instance.
+fn write_instance_lock(lock_path: &Path, instance_lock: &InstanceLock) -> std::io::Result<()> {
+    let contents = serde_json::to_string(instance_lock)?;
+
+    let mut lock_file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(lock_path)?;
+
+    lock_file.write_all(contents.as_bytes())
+}
+
+/// Sends a request to the running instance (e.g. to focus its window).
+///
+/// Returns `false` if nothing acknowledged the request, meaning the instance isn't running.
+///
+/// # Arguments
+///
+/// * `port` - The port the running instance listens on.
+/// * `request` - The request to send (e.g. `FOCUS_REQUEST`).
+async fn send_request(port: u16, request: &str) -> bool {
+    let request = async {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+        stream
+            .write_all(format!("{}\n", request).as_bytes())
+            .await?;
+
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).await?;
+
+        Ok::<bool, std::io::Error>(reply.trim() == FOCUS_ACKNOWLEDGEMENT)
+    };
+
+    matches!(
+        tokio::time::timeout(FOCUS_REQUEST_TIMEOUT, request).await,
+        Ok(Ok(true))
+    )
+}
+
+/// Listens for requests from other instances, focusing the window for each focus request.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `listener` - The listener for focus requests.
+async fn listen_for_focus_requests(app: AppHandle, listener: TcpListener) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+
+            // Errors like running out of file descriptors would fail again straight away.
+            Err(err) => {
+                println!("Failed to accept a connection from another instance: {}", err);
+                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+
+                continue;
+            }
+        };
+
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+
+        // A connection that never sends a request shouldn't block the ones after it.
+        let read_result =
+            tokio::time::timeout(REQUEST_READ_TIMEOUT, reader.read_line(&mut request)).await;
+
+        if !matches!(read_result, Ok(Ok(_))) {
+            continue;
+        }
+
+        match request.trim() {
+            FOCUS_REQUEST => {
+                println!("Another instance was launched, focusing the window");
+                focus_window(&app);
+            }
+
+            PING_REQUEST => {}
+
+            _ => continue,
+        }
+
+        let _ = reader
+            .into_inner()
+            .write_all(format!("{}\n", FOCUS_ACKNOWLEDGEMENT).as_bytes())
+            .await;
+    }
+}
+
+/// Focuses the main window, or the splashscreen if setup is still running.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+fn focus_window(app: &AppHandle) {
+    let window = app
+        .get_webview_window("splashscreen")
+        .or_else(|| app.get_webview_window("main"));
+
+    if let Some(window) = window {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
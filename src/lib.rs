@@ -1,6 +1,33 @@
-use tauri::{AppHandle, Manager, RunEvent, async_runtime::spawn};
+use std::{path::{Path, PathBuf}, time::Duration};
 
-use crate::error::AppError;
+use serde::Serialize;
+use tauri::{
+    AppHandle, Emitter, Manager, RunEvent, State, async_runtime::spawn, webview::PageLoadEvent,
+};
+
+use crate::{
+    backup::{BackupResult, VacuumResult},
+    config::{ConfigValidationError, LlmChatConfig, NoticeConfig},
+    container::{
+        ContainerStatus, DiskUsage, ExecResult, ImagePresence, InfrastructureSummary,
+        ManagedResources, PortMapping, PurgeServiceResult, StackHealth,
+    },
+    docker::DockerApiVersion,
+    doctor::{DoctorCheckResult, DoctorContext},
+    error::AppError,
+    events::{
+        APP_INFO_EVENT, AppEvent, AppInfo, INFRASTRUCTURE_SUMMARY_EVENT, NOTICE_EVENT,
+        PULL_CANCELLED_EVENT, PullCancelled, RESOURCE_OUTCOME_EVENT, SetupProgressPhase,
+        SetupRetry, WEBUI_LOAD_TIMEOUT_EVENT, WEBUI_LOADED_EVENT, WebUiLoadTimeout, WebUiLoaded,
+    },
+    instance::InstanceLockOutcome,
+    models::ModelInfo,
+    progress::{ProgressReporter, TauriProgressReporter},
+    state::{ActivePulls, AppState, RuntimeState, SetupHistory, SetupPhase},
+};
+
+/// Backs up, restores, and compacts the Open WebUI data.
+pub mod backup;
 
 /// Config options for the app.
 pub mod config;
@@ -8,94 +35,533 @@ pub mod config;
 /// Functions for interacting with container APIs (Docker or Docker-compatible).
 pub mod container;
 
+/// Functions for connecting to Docker (or Docker-compatible APIs).
+pub mod docker;
+
+/// Checks for (and fixes) common problems.
+pub mod doctor;
+
 /// Error types and utilities.
 pub mod error;
 
+/// Events emitted to the frontend.
+pub mod events;
+
+/// Makes sure only one instance of the app runs at a time.
+pub mod instance;
+
+/// Preloads and lists models.
+pub mod models;
+
+/// Pulls images in the background, so they're cached before they're needed.
+pub mod prewarm;
+
+/// Reports the progress of setup and image pulls.
+pub mod progress;
+
+/// Runtime state managed by the app.
+pub mod state;
+
+/// Checks for newer versions of the images.
+pub mod updates;
+
 /// Generic shared utilities for the app.
 pub mod utils;
 
 async fn setup(app: AppHandle) -> Result<(), AppError> {
+    let app_info = AppInfo::current();
+    println!(
+        "LocalLLM Chat {} ({}, {}, {}/{}, Tauri {})",
+        app_info.version,
+        app_info.git_commit,
+        app_info.build_profile,
+        app_info.target_os,
+        app_info.target_arch,
+        app_info.tauri_version
+    );
+
+    let _ = app.emit(APP_INFO_EVENT, &app_info);
+
+    let reporter = TauriProgressReporter::new(app.clone());
+
     // Set up the local appdata directory.
     println!("Setting up...");
     let setup_result = utils::setup_local_appdata(&app);
 
+    // The config hasn't been loaded yet, so these errors are always shown in a native dialog too.
     if let Err(setup_error) = setup_result {
+        reporter.error(&setup_error);
         utils::show_setup_local_appdata_error(&app, &setup_error);
 
         return Err(setup_error);
     }
 
+    // Fetch the config first, if it's hosted at a URL.
+    if let Err(err) = utils::fetch_remote_config(&app).await {
+        reporter.error(&err);
+        utils::show_setup_local_appdata_error(&app, &err);
+
+        return Err(err);
+    }
+
     let app_config = match utils::get_app_config(&app) {
         Ok(config) => config,
 
         Err(err) => {
+            reporter.error(&err);
             utils::show_setup_local_appdata_error(&app, &err);
 
             return Err(err);
         }
     };
 
+    if let Some(notice) = &app_config.notice {
+        let _ = app.emit(NOTICE_EVENT, notice);
+    }
+
+    // Errors are always emitted as `setup-error` events, so the splashscreen can show them,
+    // and are also shown in a native dialog unless `native_error_dialogs` is disabled.
+    let native_error_dialogs = app_config.native_error_dialogs;
+    let report_error = |err: &AppError, show_dialog: fn(&AppHandle, &AppError)| {
+        reporter.error(err);
+
+        if native_error_dialogs {
+            show_dialog(&app, err);
+        }
+    };
+
+    // Make sure another instance isn't already managing the containers.
+    if app_config.single_instance {
+        match instance::acquire_instance_lock(&app).await {
+            Ok(InstanceLockOutcome::Acquired) => {}
+
+            Ok(InstanceLockOutcome::AlreadyRunning(pid)) => {
+                println!("The app is already running (PID {}), exiting", pid);
+                app.exit(0);
+
+                return Ok(());
+            }
+
+            Err(err) => {
+                report_error(&err, utils::show_setup_local_appdata_error);
+
+                return Err(err);
+            }
+        }
+    }
+
+    // Offer to move the data from the app data directory when `data_dir` is set,
+    // before anything is created at the new location.
+    match utils::get_data_dir_to_migrate(&app, &app_config) {
+        Ok(Some((old_data_dir, new_data_dir))) => {
+            if utils::confirm_data_dir_migration(&app, &old_data_dir, &new_data_dir) {
+                if let Err(err) = utils::migrate_data_dir(&old_data_dir, &new_data_dir) {
+                    report_error(&err, utils::show_setup_local_appdata_error);
+
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(None) => {}
+
+        Err(err) => println!("Failed to check for a data directory to migrate: {}", err),
+    }
+
     // Get the data directory path for the Open WebUI container.
-    let data_dir = match utils::get_app_container_dir(&app) {
-        Ok(container_dir) => container_dir.join("data"),
+    let data_dir = match utils::get_app_data_dir(&app, &app_config) {
+        Ok(data_dir) => data_dir,
 
         Err(err) => {
-            utils::show_setup_local_appdata_error(&app, &err);
+            report_error(&err, utils::show_setup_local_appdata_error);
 
             return Err(err);
         }
     };
 
-    // Pull the container images needed for the application.
-    println!("Pulling container images");
-    if let Err(container_err) = container::pull_required_images(&app_config).await {
-        utils::show_docker_error(&app, &container_err);
+    // Connect to Docker, waiting for it to become available if it's still starting.
+    let docker = match docker::connect_docker_with_retry(&app_config).await {
+        Ok(docker) => docker,
+
+        Err(err) => {
+            report_error(&err, utils::show_docker_error);
+
+            return Err(err);
+        }
+    };
+
+    // Check that the engine can actually run containers, if enabled.
+    if app_config.engine_self_test {
+        reporter.log("Checking that the container engine can run containers");
+
+        let self_test_result = container::engine_self_test(
+            &reporter,
+            &docker,
+            &app_config,
+            &app.state::<ActivePulls>(),
+        )
+        .await;
+
+        if !self_test_result.passed {
+            let self_test_err = AppError::EngineSelfTestFailed(self_test_result.detail);
+            report_error(&self_test_err, utils::show_docker_error);
+
+            return Err(self_test_err);
+        }
+
+        reporter.log(&format!("Container engine self-test passed: {}", self_test_result.detail));
+    }
 
-        return Err(container_err);
+    // Ollama might just not be started yet, so an unreachable external Ollama isn't fatal.
+    if let Some(external_ollama_url) = &app_config.external_ollama_url {
+        if !utils::is_external_ollama_reachable(external_ollama_url).await {
+            reporter.log(&format!(
+                "Warning: The external Ollama at '{}' can't be reached",
+                external_ollama_url
+            ));
+        }
     }
 
-    // Clean up any dangling container resources before running.
-    // This *shouldn't* be needed, but, in the event that something catastrophically
-    // occurred in a previous session, this can clean up those leftover resources.
-    println!("Cleaning up previous containers, if needed");
-    if let Err(container_err) = container::cleanup_infrastructure(&app_config).await {
-        utils::show_docker_error(&app, &container_err);
+    let runtime_state_path = utils::get_runtime_state_path(&app)?;
+    let mut runtime_state = RuntimeState::load(&runtime_state_path);
+
+    // Store the config and Docker client in the app's state,
+    // so they don't need to be re-read later.
+    app.manage(AppState::new(app_config, runtime_state.get_host_port(), docker));
 
-        return Err(container_err);
+    let app_state = app.state::<AppState>();
+    let app_config = &app_state.config();
+    let docker = &app_state.docker;
+
+    if !runtime_state.completed_setup_phases.is_empty() {
+        reporter.log("Resuming setup from a previous launch");
     }
 
-    // Start the containers.
-    println!("Starting container");
-    if let Err(container_err) = container::create_infrastructure(&app_config, &data_dir).await {
-        utils::show_docker_error(&app, &container_err);
+    // Transient failures, like a Docker daemon hiccup, retry the whole setup,
+    // but only the last attempt's error is reported.
+    let max_attempts = app_config.setup_retries + 1;
+
+    for attempt in 1..=max_attempts {
+        let report_attempt_error = |err: &AppError, show_dialog: fn(&AppHandle, &AppError)| {
+            if attempt == max_attempts || !err.is_transient() {
+                report_error(err, show_dialog);
+            }
+        };
+
+        let setup_result = setup_containers(
+            &app,
+            &reporter,
+            &app_state,
+            &data_dir,
+            &mut runtime_state,
+            &runtime_state_path,
+            &report_attempt_error,
+        )
+        .await;
+
+        match setup_result {
+            Ok(()) => break,
 
-        container::cleanup_infrastructure(&app_config).await?;
+            Err(err) if attempt < max_attempts && err.is_transient() => {
+                reporter.retry(SetupRetry {
+                    attempt: attempt + 1,
+                    max_attempts,
+                    message: err.to_string(),
+                });
 
-        return Err(container_err);
+                if let Err(cleanup_err) =
+                    container::cleanup_infrastructure(docker, app_config, false).await
+                {
+                    reporter.log(&format!(
+                        "Failed to clean up before retrying setup: {}",
+                        cleanup_err
+                    ));
+                }
+
+                tokio::time::sleep(SETUP_RETRY_INTERVAL).await;
+            }
+
+            Err(err) => return Err(err),
+        }
     }
 
-    // Wait until the Open WebUI container is healthy.
-    utils::wait_until_openwebui_is_healthy(&app).await?;
+    reporter.phase(SetupProgressPhase::Ready);
 
     setup_complete(app.clone()).await.unwrap();
 
+    if let Some(post_setup_hook) = app_config.post_setup_hook.clone() {
+        spawn(utils::run_post_setup_hook(
+            post_setup_hook,
+            app_state.webui_url(),
+            app_state.host_port(),
+        ));
+    }
+
+    // Preload models in Ollama in the background, if any are configured.
+    spawn(models::preload_models(
+        app.clone(),
+        docker.clone(),
+        LlmChatConfig::clone(app_config),
+    ));
+
+    // Pre-warm images in the background, if any are configured.
+    if app_config.prewarm_images.is_some() {
+        let prewarm_task = spawn(prewarm::prewarm_images(
+            app.clone(),
+            docker.clone(),
+            LlmChatConfig::clone(app_config),
+        ));
+
+        if let Ok(mut app_prewarm_task) = app_state.prewarm_task.lock() {
+            *app_prewarm_task = Some(prewarm_task);
+        }
+    }
+
+    // Check for newer Open WebUI images in the background, if enabled.
+    spawn(updates::run_update_checks(
+        app.clone(),
+        docker.clone(),
+        LlmChatConfig::clone(app_config),
+    ));
+
+    Ok(())
+}
+
+/// The time to wait before retrying setup after a transient error.
+const SETUP_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Pulls the images, sets up the containers, and waits for Open WebUI to be healthy.
+///
+/// This is the part of setup that's retried on a transient error, resuming from
+/// the phases the previous attempt completed.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `reporter` - Receives the progress of setup.
+/// * `app_state` - The app's state, with the config and Docker client.
+/// * `data_dir` - The data directory for the Open WebUI container.
+/// * `runtime_state` - The state persisted between launches.
+/// * `runtime_state_path` - The path the runtime state is saved to.
+/// * `report_error` - Reports an error that fails setup, with the dialog to show it in.
+async fn setup_containers(
+    app: &AppHandle,
+    reporter: &TauriProgressReporter,
+    app_state: &AppState,
+    data_dir: &PathBuf,
+    runtime_state: &mut RuntimeState,
+    runtime_state_path: &Path,
+    report_error: &(dyn Fn(&AppError, fn(&AppHandle, &AppError)) + Sync),
+) -> Result<(), AppError> {
+    let app_config = &app_state.config();
+    let docker = &app_state.docker;
+
+    // Skip the pull when resuming, as long as the images are still there.
+    let images_already_pulled = runtime_state.has_completed_phase(SetupPhase::ImagesPulled)
+        && container::check_images_present(docker, app_config)
+            .await
+            .is_ok_and(|images_presence| images_presence.iter().all(|image| image.present));
+
+    if images_already_pulled {
+        reporter.log("Skipping the image pull, since the images were pulled by the previous setup");
+    } else {
+        // Pull the container images needed for the application.
+        reporter.phase(SetupProgressPhase::PullingImages);
+        reporter.log("Pulling container images");
+        let pull_result = container::pull_required_images(
+            reporter,
+            docker,
+            app_config,
+            &app.state::<ActivePulls>(),
+        )
+        .await;
+
+        // A cancelled pull fails setup like any other failed pull,
+        // so it's retried on the next launch.
+        if let Err(container_err) = pull_result {
+            report_error(&container_err, utils::show_docker_error);
+
+            return Err(container_err);
+        }
+
+        // Track which images were replaced by the pull, so they can be pruned
+        // once the containers are running on the new ones.
+        if let Err(err) = container::track_replaced_images(docker, app_config, runtime_state).await {
+            reporter.log(&format!("Failed to track replaced images: {}", err));
+        }
+
+        runtime_state.mark_phase_completed(SetupPhase::ImagesPulled, runtime_state_path);
+    }
+
+    // Docker creates the host paths and volumes for the services that don't exist yet,
+    // so they're tracked as the app's own, to be removed if the service is purged.
+    if let Err(err) = runtime_state.track_service_volumes(docker, app_config).await {
+        reporter.log(&format!("Failed to track the services' volumes: {}", err));
+    }
+
+    // When resuming, the containers from the previous setup are reused where they're still current.
+    let resuming_infrastructure = runtime_state.has_completed_phase(SetupPhase::InfrastructureReady);
+
+    // Containers left behind by a session that crashed before cleaning up are adopted
+    // where they still match the config, instead of being torn down and recreated.
+    let adopt_infrastructure = app_config.cleanup_on_exit
+        && !resuming_infrastructure
+        && container::has_managed_containers(docker).await.unwrap_or(false);
+
+    if adopt_infrastructure {
+        reporter.log("Found containers from a previous session, adopting the ones that match the config");
+    }
+
+    let reuse_infrastructure =
+        !app_config.cleanup_on_exit || resuming_infrastructure || adopt_infrastructure;
+
+    if reuse_infrastructure {
+        // The containers from the previous session (or setup) are kept around,
+        // so reuse them where possible instead of tearing everything down.
+        reporter.phase(SetupProgressPhase::ReconcilingInfrastructure);
+        reporter.log("Reconciling containers from the previous session");
+        match container::reconcile_infrastructure(docker, app_config, data_dir, app_state.host_port())
+            .await
+        {
+            Ok(infrastructure_summary) => report_infrastructure_summary(app, &infrastructure_summary),
+
+            Err(container_err) => {
+                report_error(&container_err, utils::show_docker_error);
+
+                return Err(container_err);
+            }
+        }
+    } else {
+        // Clean up any dangling container resources before running.
+        // This *shouldn't* be needed, but, in the event that something catastrophically
+        // occurred in a previous session, this can clean up those leftover resources.
+        reporter.phase(SetupProgressPhase::CreatingInfrastructure);
+        reporter.log("Cleaning up previous containers, if needed");
+        if let Err(container_err) =
+            container::cleanup_infrastructure(docker, app_config, false).await
+        {
+            report_error(&container_err, utils::show_docker_error);
+
+            return Err(container_err);
+        }
+
+        // Start the containers.
+        reporter.log("Starting container");
+        let infrastructure_summary = match container::create_infrastructure(
+            docker,
+            app_config,
+            data_dir,
+            app_state.host_port(),
+        )
+        .await
+        {
+            Ok(infrastructure_summary) => infrastructure_summary,
+
+            // The resources created in this attempt were already removed.
+            Err(container_err) => {
+                report_error(&container_err, utils::show_docker_error);
+
+                return Err(container_err);
+            }
+        };
+
+        report_infrastructure_summary(app, &infrastructure_summary);
+    }
+
+    runtime_state.mark_phase_completed(SetupPhase::InfrastructureReady, runtime_state_path);
+
+    // Run the init command, if any, before Open WebUI is used.
+    if let Some(init_command) = app_config.init_command.clone() {
+        reporter.phase(SetupProgressPhase::RunningInitCommand);
+        if let Err(init_err) = container::run_init_command(docker, init_command).await {
+            report_error(&init_err, utils::show_docker_error);
+
+            return Err(init_err);
+        }
+    }
+
+    // Wait until the Open WebUI container is healthy.
+    reporter.phase(SetupProgressPhase::WaitingForOpenWebUi);
+    if !utils::wait_for_openwebui_health(reporter, &app_state.webui_url()).await {
+        let health_err = AppError::GenericError("Startup took too long".to_string());
+        report_error(&health_err, utils::show_setup_error);
+
+        return Err(health_err);
+    }
+
+    if app_config.prune_old_images {
+        reporter.log("Pruning replaced images, if any");
+        if let Err(err) = container::prune_replaced_images(docker, runtime_state).await {
+            reporter.log(&format!("Failed to prune replaced images: {}", err));
+        }
+    }
+
+    // Setup finished, so the next launch starts from the beginning.
+    runtime_state.completed_setup_phases.clear();
+
+    if let Err(err) = runtime_state.save(runtime_state_path) {
+        reporter.log(&format!("Failed to save runtime state: {}", err));
+    }
+
     Ok(())
 }
 
+/// Logs what was created, reused, or recreated and emits it to the frontend.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `infrastructure_summary` - The summary from setting up the infrastructure.
+fn report_infrastructure_summary(app: &AppHandle, infrastructure_summary: &InfrastructureSummary) {
+    println!("Infrastructure ready ({})", infrastructure_summary);
+
+    for warning in &infrastructure_summary.warnings {
+        println!("Warning: {}", warning);
+    }
+
+    for resource in &infrastructure_summary.resources {
+        let _ = app.emit(RESOURCE_OUTCOME_EVENT, resource);
+    }
+
+    let _ = app.emit(INFRASTRUCTURE_SUMMARY_EVENT, infrastructure_summary);
+}
+
 #[tauri::command]
 async fn setup_complete(app: AppHandle) -> Result<(), ()> {
-    let splashscreen_window = app.get_webview_window("splashscreen").unwrap();
-
-    splashscreen_window.close().unwrap();
+    let webui_url = app.state::<AppState>().webui_url();
 
+    // The main window is kept hidden, with the splashscreen still showing,
+    // until Open WebUI has actually loaded, so there isn't a blank window in between.
     let webview_window_builder = tauri::WebviewWindowBuilder::new(
         &app,
         "main",
-        tauri::WebviewUrl::App("http://localhost:11690".into()),
+        tauri::WebviewUrl::App(webui_url.clone().into()),
     )
     .title("LocalLLM Chat")
     .inner_size(1280.0, 800.0)
-    .disable_drag_drop_handler();
+    .visible(false)
+    .disable_drag_drop_handler()
+    .on_page_load(|window, payload| {
+        if payload.event() != PageLoadEvent::Finished {
+            return;
+        }
+
+        println!("Open WebUI finished loading: {}", payload.url());
+
+        let _ = window.show();
+        let _ = window.set_focus();
+
+        if let Some(splashscreen_window) = window.app_handle().get_webview_window("splashscreen") {
+            let _ = splashscreen_window.close();
+        }
+
+        let _ = window.emit(
+            WEBUI_LOADED_EVENT,
+            WebUiLoaded {
+                url: payload.url().to_string(),
+            },
+        );
+    });
 
     #[cfg(target_os = "macos")]
     let webview_window_builder =
@@ -103,6 +569,894 @@ async fn setup_complete(app: AppHandle) -> Result<(), ()> {
 
     let _ = webview_window_builder.build().unwrap();
 
+    let load_timeout =
+        Duration::from_secs(app.state::<AppState>().config().webview_load_timeout_secs);
+    spawn(check_webui_loaded(app.clone(), webui_url, load_timeout));
+
+    Ok(())
+}
+
+/// Checks that Open WebUI loaded in the main window, once the timeout has passed.
+///
+/// Some VPNs and firewalls stop the window from reaching `localhost`, even though the
+/// health check from the app passed, which leaves the window blank. When that happens,
+/// the user is offered to open Open WebUI in the system browser instead.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `webui_url` - The URL for Open WebUI.
+/// * `load_timeout` - How long to wait for it to load.
+async fn check_webui_loaded(app: AppHandle, webui_url: String, load_timeout: Duration) {
+    tokio::time::sleep(load_timeout).await;
+
+    // The main window is only shown once Open WebUI has finished loading.
+    let is_loaded = match app.get_webview_window("main") {
+        Some(main_window) => main_window.is_visible().unwrap_or(true),
+
+        // The window was closed, so the app is exiting.
+        None => return,
+    };
+
+    if is_loaded {
+        return;
+    }
+
+    println!(
+        "Warning: Open WebUI didn't load in the main window within {} seconds",
+        load_timeout.as_secs()
+    );
+
+    let _ = app.emit(
+        WEBUI_LOAD_TIMEOUT_EVENT,
+        WebUiLoadTimeout {
+            url: webui_url.clone(),
+            timeout_secs: load_timeout.as_secs(),
+            message: "Open WebUI is running, but the app window can't reach it. A VPN or firewall may be blocking localhost.".to_string(),
+        },
+    );
+
+    utils::show_webui_load_timeout(&app, &webui_url);
+}
+
+/// Gets the URL for Open WebUI.
+#[tauri::command]
+fn get_webui_url(state: State<'_, AppState>) -> String {
+    state.webui_url()
+}
+
+/// Checks once if Open WebUI is ready, so the frontend can poll it.
+#[tauri::command]
+async fn is_webui_ready(app: AppHandle) -> Result<bool, AppError> {
+    // Setup hasn't gotten far enough to start Open WebUI yet.
+    let Some(app_state) = app.try_state::<AppState>() else {
+        return Ok(false);
+    };
+
+    utils::is_openwebui_ready(&app_state.webui_url()).await
+}
+
+/// Lists the models available in Open WebUI.
+#[tauri::command]
+async fn list_models(state: State<'_, AppState>) -> Result<Vec<ModelInfo>, AppError> {
+    models::list_models(&state.webui_url(), state.config().openwebui_api_key.as_deref()).await
+}
+
+/// Gets the config as the app sees it, with the active profile merged in and secrets redacted.
+#[tauri::command]
+fn get_effective_config(app: AppHandle) -> Result<LlmChatConfig, AppError> {
+    // The app state might not exist if setup failed early, so fall back to reading the config.
+    match app.try_state::<AppState>() {
+        Some(app_state) => Ok(app_state.config().redacted()),
+
+        None => Ok(utils::get_app_config(&app)?.redacted()),
+    }
+}
+
+/// Validates a candidate config, without saving it.
+///
+/// The YAML is parsed the same way as the config file, with the active profile merged in,
+/// so an in-app editor can show the problems before the changes are saved.
+#[tauri::command]
+fn validate_config(config_yaml: String) -> Result<Vec<ConfigValidationError>, AppError> {
+    let app_config =
+        LlmChatConfig::from_yaml_with_profile(&config_yaml, utils::get_profile_override())?;
+
+    Ok(app_config.validate())
+}
+
+/// Gets the status of the containers managed by the app.
+#[tauri::command]
+async fn get_container_statuses(
+    state: State<'_, AppState>,
+) -> Result<Vec<ContainerStatus>, AppError> {
+    container::get_container_statuses(&state.docker).await
+}
+
+/// Gets the host-to-container port bindings of the running containers managed by the app.
+#[tauri::command]
+async fn get_port_mappings(state: State<'_, AppState>) -> Result<Vec<PortMapping>, AppError> {
+    container::get_port_mappings(&state.docker, &state.config()).await
+}
+
+/// Gets the health of the whole stack, for a single "is everything OK?" indicator.
+#[tauri::command]
+async fn get_stack_health(state: State<'_, AppState>) -> Result<StackHealth, AppError> {
+    container::get_stack_health(&state.docker, &state.config(), &state.webui_url()).await
+}
+
+/// Gets the Docker API version the app uses, and the versions the Docker engine supports.
+///
+/// Setup fails before the app's state exists when the engine rejects the version,
+/// so this connects to Docker on its own in that case.
+#[tauri::command]
+async fn get_docker_api_version(app: AppHandle) -> Result<DockerApiVersion, AppError> {
+    if let Some(app_state) = app.try_state::<AppState>() {
+        return docker::get_docker_api_version(&app_state.docker, &app_state.config()).await;
+    }
+
+    let app_config = utils::get_app_config(&app)?;
+    let docker = docker::connect_docker(&app_config)?;
+
+    docker::get_docker_api_version(&docker, &app_config).await
+}
+
+/// Checks whether each image required by the app is present locally.
+#[tauri::command]
+async fn check_images_present(state: State<'_, AppState>) -> Result<Vec<ImagePresence>, AppError> {
+    container::check_images_present(&state.docker, &state.config()).await
+}
+
+/// Loads images from an archive created with `docker save`, for hosts that can't pull.
+///
+/// Returns the images the config expects that were loaded from the archive.
+#[tauri::command]
+async fn import_image(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<String>, AppError> {
+    let reporter = TauriProgressReporter::new(app.clone());
+
+    container::load_image_archive(
+        &reporter,
+        &state.docker,
+        &state.config(),
+        &PathBuf::from(path),
+    )
+    .await
+}
+
+/// Gets details about the build of the app, for the About dialog and bug reports.
+#[tauri::command]
+fn get_app_info() -> AppInfo {
+    AppInfo::current()
+}
+
+/// Gets the `notice` from the config, if it has one.
+#[tauri::command]
+fn get_notice(app: AppHandle) -> Result<Option<NoticeConfig>, AppError> {
+    // The app state might not exist yet while setup is running, so fall back to reading the config.
+    match app.try_state::<AppState>() {
+        Some(app_state) => Ok(app_state.config().notice.clone()),
+
+        None => Ok(utils::get_app_config(&app)?.notice),
+    }
+}
+
+/// Compacts the Open WebUI database, after the user confirms.
+///
+/// Returns the size of the database before and after.
+#[tauri::command]
+async fn vacuum_database(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<VacuumResult, AppError> {
+    let data_dir = utils::get_app_data_dir(&app, &state.config())?;
+
+    backup::vacuum_database(&app, &state.docker, &data_dir).await
+}
+
+/// Gets the disk usage of the images, data directory, and volumes managed by the app.
+#[tauri::command]
+async fn get_disk_usage(app: AppHandle, state: State<'_, AppState>) -> Result<DiskUsage, AppError> {
+    let data_dir = utils::get_app_data_dir(&app, &state.config())?;
+
+    container::get_disk_usage(&state.docker, &state.config(), &data_dir).await
+}
+
+/// Gets the names of the containers and networks the app manages for the active config.
+#[tauri::command]
+fn get_managed_resources(app: AppHandle) -> Result<ManagedResources, AppError> {
+    // The app state might not exist if setup failed early, so fall back to reading the config.
+    match app.try_state::<AppState>() {
+        Some(app_state) => Ok(container::get_managed_resources(&app_state.config())),
+
+        None => Ok(container::get_managed_resources(&utils::get_app_config(&app)?)),
+    }
+}
+
+/// Runs a command in a running container managed by the app.
+#[tauri::command]
+async fn exec_in_container(
+    state: State<'_, AppState>,
+    name: String,
+    cmd: Vec<String>,
+) -> Result<ExecResult, AppError> {
+    container::exec_in_container(&state.docker, &name, cmd).await
+}
+
+/// Backs up the Open WebUI data directory to a zip file chosen by the user.
+///
+/// Open WebUI is stopped while the data is archived. Returns the path to the backup and
+/// the symlinks and special files that were skipped.
+#[tauri::command]
+async fn backup_data(app: AppHandle, state: State<'_, AppState>) -> Result<BackupResult, AppError> {
+    let data_dir = utils::get_app_data_dir(&app, &state.config())?;
+
+    backup::backup_data(
+        &app,
+        &state.docker,
+        &data_dir,
+        state.config().openwebui_stop_timeout_secs,
+    )
+    .await
+}
+
+/// Restores the Open WebUI data directory from a zip file chosen by the user, after confirming.
+///
+/// Open WebUI is stopped while the data is replaced. Returns the path to the backup that was restored.
+#[tauri::command]
+async fn restore_data(app: AppHandle, state: State<'_, AppState>) -> Result<PathBuf, AppError> {
+    let data_dir = utils::get_app_data_dir(&app, &state.config())?;
+
+    backup::restore_data(
+        &app,
+        &state.docker,
+        &data_dir,
+        state.config().openwebui_stop_timeout_secs,
+    )
+    .await
+}
+
+/// Removes and recreates a single service's container with the current config.
+///
+/// The config is re-read, so changes to the service are picked up without restarting the app.
+/// When Open WebUI is recreated, this waits until it's healthy again.
+#[tauri::command]
+async fn recreate_service(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), AppError> {
+    let app_config = utils::get_app_config(&app)?;
+    let data_dir = utils::get_app_data_dir(&app, &app_config)?;
+
+    container::recreate_service(
+        &state.docker,
+        &app_config,
+        &data_dir,
+        state.host_port(),
+        &name,
+    )
+    .await?;
+
+    if name == "openwebui" {
+        utils::wait_until_openwebui_is_healthy(&app, &state.webui_url()).await?;
+    }
+
+    Ok(())
+}
+
+/// Removes an extra backend service's container and, after the user confirms,
+/// the host paths that were created for it.
+///
+/// This also works for services that were removed from the config. Host paths and named
+/// volumes that existed before the service was created aren't removed, since they belong
+/// to the user.
+///
+/// A path, volume, or image that can't be removed doesn't stop the rest from being removed.
+/// The failures are returned in the result's `errors`.
+///
+/// # Arguments
+///
+/// * `name` - The name of the extra backend service.
+/// * `remove_image` - Whether to remove the service's image too, if nothing else uses it.
+#[tauri::command]
+async fn purge_service(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    remove_image: Option<bool>,
+) -> Result<PurgeServiceResult, AppError> {
+    let image = container::remove_extra_service_container(&state.docker, &name).await?;
+
+    let mut purge_result = PurgeServiceResult {
+        container_removed: image.is_some(),
+        ..Default::default()
+    };
+
+    let runtime_state_path = utils::get_runtime_state_path(&app)?;
+    let mut runtime_state = RuntimeState::load(&runtime_state_path);
+
+    let (volume_paths, mut volumes): (Vec<String>, Vec<String>) = runtime_state
+        .service_volume_paths
+        .get(&name)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|volume_path| !container::is_named_volume(volume_path));
+
+    // Named volumes were tracked as host paths by earlier versions.
+    for volume in runtime_state.service_volumes.get(&name).into_iter().flatten() {
+        if !volumes.contains(volume) {
+            volumes.push(volume.clone());
+        }
+    }
+
+    if (!volume_paths.is_empty() || !volumes.is_empty())
+        && utils::confirm_purge_service_data(&app, &name, &volume_paths, &volumes).await
+    {
+        let mut remaining_volume_paths = Vec::new();
+
+        for volume_path in volume_paths {
+            match utils::remove_path(Path::new(&volume_path)) {
+                Ok(true) => {
+                    println!("Removed '{}' for '{}'", volume_path, name);
+                    purge_result.removed_paths.push(volume_path);
+                }
+
+                Ok(false) => {}
+
+                Err(err) => {
+                    purge_result
+                        .errors
+                        .push(format!("Failed to remove '{}': {}", volume_path, err));
+                    remaining_volume_paths.push(volume_path);
+                }
+            }
+        }
+
+        let mut remaining_volumes = Vec::new();
+
+        for volume in volumes {
+            match container::remove_volume(&state.docker, &volume).await {
+                Ok(true) => purge_result.removed_volumes.push(volume),
+
+                Ok(false) => {}
+
+                Err(err) => {
+                    purge_result
+                        .errors
+                        .push(format!("Failed to remove volume '{}': {}", volume, err));
+                    remaining_volumes.push(volume);
+                }
+            }
+        }
+
+        // The ones that failed stay tracked, so purging again retries them.
+        runtime_state.service_volume_paths.remove(&name);
+        runtime_state.service_volumes.remove(&name);
+
+        if !remaining_volume_paths.is_empty() {
+            runtime_state.service_volume_paths.insert(name.clone(), remaining_volume_paths);
+        }
+
+        if !remaining_volumes.is_empty() {
+            runtime_state.service_volumes.insert(name.clone(), remaining_volumes);
+        }
+
+        if let Err(err) = runtime_state.save(&runtime_state_path) {
+            purge_result
+                .errors
+                .push(format!("Failed to save the runtime state: {}", err));
+        }
+    }
+
+    if let Some(image) = image.filter(|_| remove_image.unwrap_or(false)) {
+        match container::remove_image_if_unused(&state.docker, &image).await {
+            Ok(image_removed) => purge_result.image_removed = image_removed,
+
+            Err(err) => purge_result
+                .errors
+                .push(format!("Failed to remove image '{}': {}", image, err)),
+        }
+    }
+
+    Ok(purge_result)
+}
+
+/// Moves Open WebUI to a different port on the host, without restarting the app.
+///
+/// The containers bound to the host port are recreated on the new port, keeping the
+/// data directory. The port is saved, so it's used on the next launch too, and the
+/// main window is moved to the new URL. If the containers can't be recreated on the
+/// new port, they're recreated on the previous port.
+#[tauri::command]
+async fn set_host_port(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    port: u16,
+) -> Result<(), AppError> {
+    if port == state.host_port() {
+        return Ok(());
+    }
+
+    if port == 0 {
+        return Err(AppError::ConfigError("0 isn't a valid host port".to_string()));
+    }
+
+    if state.config().tika_host_port == Some(port) {
+        return Err(AppError::ConfigError(format!(
+            "Port {} is already used by Tika (`tika_host_port`)",
+            port
+        )));
+    }
+
+    if !utils::is_host_port_available(port) {
+        return Err(AppError::GenericError(format!(
+            "Port {} is already in use by another application",
+            port
+        )));
+    }
+
+    let data_dir = utils::get_app_data_dir(&app, &state.config())?;
+    let previous_port = state.host_port();
+
+    let recreate_result =
+        recreate_host_port_services(&state.docker, &state.config(), &data_dir, port).await;
+
+    if let Err(err) = recreate_result {
+        println!(
+            "Failed to move Open WebUI to port {}, rolling back to port {}: {}",
+            port, previous_port, err
+        );
+
+        recreate_host_port_services(&state.docker, &state.config(), &data_dir, previous_port)
+            .await?;
+
+        return Err(AppError::GenericError(format!(
+            "Open WebUI couldn't be moved to port {}, so it was kept on port {}: {}",
+            port, previous_port, err
+        )));
+    }
+
+    state.set_host_port(port);
+
+    let runtime_state_path = utils::get_runtime_state_path(&app)?;
+    let mut runtime_state = RuntimeState::load(&runtime_state_path);
+    runtime_state.host_port = Some(port);
+    runtime_state.save(&runtime_state_path)?;
+
+    println!("Moved Open WebUI to port {}", port);
+
+    utils::wait_until_openwebui_is_healthy(&app, &state.webui_url()).await?;
+
+    if let Some(main_window) = app.get_webview_window("main") {
+        let webui_url = state
+            .webui_url()
+            .parse::<tauri::Url>()
+            .map_err(|e| AppError::GenericError(format!("Invalid Open WebUI URL: {}", e)))?;
+
+        main_window
+            .navigate(webui_url)
+            .map_err(|e| AppError::TauriError(e))?;
+    }
+
+    Ok(())
+}
+
+/// Recreates the containers that are bound to the host port on a port.
+///
+/// Open WebUI is recreated even with a reverse proxy, since its URL includes the port.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `app_config` - The app config.
+/// * `data_dir` - The path to the Open WebUI data directory.
+/// * `port` - The port on the host.
+async fn recreate_host_port_services(
+    docker: &bollard::Docker,
+    app_config: &LlmChatConfig,
+    data_dir: &PathBuf,
+    port: u16,
+) -> Result<(), AppError> {
+    container::recreate_service(docker, app_config, data_dir, port, "openwebui").await?;
+
+    if app_config.reverse_proxy.is_some() {
+        container::recreate_service(
+            docker,
+            app_config,
+            data_dir,
+            port,
+            config::REVERSE_PROXY_SERVICE_NAME,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Pauses the containers managed by the app.
+///
+/// This is quicker than stopping them, and frees up the CPU they were using,
+/// but Open WebUI won't respond until they're unpaused.
+#[tauri::command]
+async fn pause_stack(state: State<'_, AppState>) -> Result<(), AppError> {
+    container::pause_containers(&state.docker, &state.config()).await
+}
+
+/// Unpauses the containers managed by the app and waits for Open WebUI to respond again.
+#[tauri::command]
+async fn unpause_stack(app: AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    container::unpause_containers(&state.docker, &state.config()).await?;
+
+    utils::wait_until_openwebui_is_healthy(&app, &state.webui_url()).await
+}
+
+/// Converges the running containers and networks to the config file.
+///
+/// Missing containers are created, containers that don't match the config are recreated,
+/// and containers for services removed from the config are removed. Containers that match
+/// are left untouched, so reconciling again without changing the config changes nothing.
+///
+/// The rest of the app keeps using the config it was launched with until it's restarted.
+#[tauri::command]
+async fn reconcile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<InfrastructureSummary, AppError> {
+    let app_config = utils::get_app_config(&app)?;
+    let data_dir = utils::get_app_data_dir(&app, &app_config)?;
+
+    // Only pull when an image is missing, so an unchanged config doesn't hit the registry.
+    let images_presence = container::check_images_present(&state.docker, &app_config).await?;
+
+    if images_presence.iter().any(|image_presence| !image_presence.present) {
+        let reporter = TauriProgressReporter::new(app.clone());
+        container::pull_required_images(
+            &reporter,
+            &state.docker,
+            &app_config,
+            &app.state::<ActivePulls>(),
+        )
+        .await?;
+    }
+
+    let runtime_state_path = utils::get_runtime_state_path(&app)?;
+    let mut runtime_state = RuntimeState::load(&runtime_state_path);
+    if let Err(err) = runtime_state.track_service_volumes(&state.docker, &app_config).await {
+        println!("Failed to track the services' volumes: {}", err);
+    }
+    runtime_state.save(&runtime_state_path)?;
+
+    let infrastructure_summary = container::reconcile_infrastructure(
+        &state.docker,
+        &app_config,
+        &data_dir,
+        state.host_port(),
+    )
+    .await?;
+
+    report_infrastructure_summary(&app, &infrastructure_summary);
+
+    Ok(infrastructure_summary)
+}
+
+/// Gets the setup events emitted so far, oldest first.
+///
+/// This lets the frontend rebuild the setup timeline after a reload, or if it
+/// started listening after setup began. Only the most recent update of each pull
+/// is kept, and the oldest events are dropped once the history is full.
+#[tauri::command]
+fn get_setup_history(app: AppHandle) -> Vec<AppEvent> {
+    app.state::<SetupHistory>().events()
+}
+
+/// Cancels image pulls that are in progress, returning the images whose pulls were cancelled.
+///
+/// If setup is pulling the image, setup fails and the pull is retried on the next launch.
+///
+/// # Arguments
+///
+/// * `image` - The image to cancel the pull of, or `None` to cancel every pull.
+#[tauri::command]
+async fn cancel_pull(app: AppHandle, image: Option<String>) -> Result<Vec<String>, AppError> {
+    let cancelled_images = app.state::<ActivePulls>().cancel(image.as_deref());
+
+    if cancelled_images.is_empty() {
+        return Err(match image {
+            Some(image) => AppError::GenericError(format!("'{}' isn't being pulled", image)),
+            None => AppError::GenericError("No images are being pulled".to_string()),
+        });
+    }
+
+    println!("Cancelled the pulls of: {}", cancelled_images.join(", "));
+
+    let _ = app.emit(
+        PULL_CANCELLED_EVENT,
+        PullCancelled {
+            images: cancelled_images.clone(),
+        },
+    );
+
+    Ok(cancelled_images)
+}
+
+/// Switches Open WebUI to a different image tag.
+///
+/// The new image is pulled, the tag is saved to the config, and the Open WebUI container
+/// is recreated on it, keeping the data directory. If Open WebUI doesn't become healthy
+/// on the new tag, the config and container are rolled back to the previous tag.
+#[tauri::command]
+async fn set_openwebui_tag(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    tag: String,
+) -> Result<(), AppError> {
+    if !config::is_valid_image_tag(&tag) {
+        return Err(AppError::ConfigError(format!(
+            "'{}' isn't a valid image tag",
+            tag
+        )));
+    }
+
+    let previous_config = utils::get_app_config(&app)?;
+    let data_dir = utils::get_app_data_dir(&app, &previous_config)?;
+
+    if previous_config.openwebui_image_tag == tag {
+        return Ok(());
+    }
+
+    let mut app_config = previous_config.clone();
+    app_config.openwebui_image_tag = tag.clone();
+
+    // Pull first, so a tag that doesn't exist fails before anything is changed.
+    let reporter = TauriProgressReporter::new(app.clone());
+    container::pull_openwebui_image(
+        &reporter,
+        &state.docker,
+        &app_config,
+        &app.state::<ActivePulls>(),
+    )
+    .await?;
+
+    utils::set_openwebui_image_tag(&app, app_config.active_profile.as_deref(), &tag)?;
+
+    let recreate_result = container::recreate_service(
+        &state.docker,
+        &app_config,
+        &data_dir,
+        state.host_port(),
+        "openwebui",
+    )
+    .await;
+
+    let is_healthy = match recreate_result {
+        Ok(_) => utils::wait_for_openwebui_health(&reporter, &state.webui_url()).await,
+
+        Err(err) => {
+            println!("Failed to recreate Open WebUI on '{}': {}", tag, err);
+
+            false
+        }
+    };
+
+    if is_healthy {
+        // Only the tag is updated, so other changes to the config file still need a restart.
+        let mut managed_config = LlmChatConfig::clone(&state.config());
+        managed_config.openwebui_image_tag = tag.clone();
+        state.set_config(managed_config);
+
+        println!("Switched Open WebUI to '{}'", tag);
+
+        return Ok(());
+    }
+
+    println!(
+        "Open WebUI didn't become healthy on '{}', rolling back to '{}'",
+        tag, previous_config.openwebui_image_tag
+    );
+
+    utils::set_openwebui_image_tag(
+        &app,
+        previous_config.active_profile.as_deref(),
+        &previous_config.openwebui_image_tag,
+    )?;
+
+    container::recreate_service(
+        &state.docker,
+        &previous_config,
+        &data_dir,
+        state.host_port(),
+        "openwebui",
+    )
+    .await?;
+
+    Err(AppError::GenericError(format!(
+        "Open WebUI didn't start on '{}', so it was rolled back to '{}'",
+        tag, previous_config.openwebui_image_tag
+    )))
+}
+
+/// Gets the last lines of a managed container's logs.
+#[tauri::command]
+async fn get_recent_logs(
+    state: State<'_, AppState>,
+    container_name: String,
+    lines: usize,
+) -> Result<Vec<String>, AppError> {
+    container::get_recent_logs(&state.docker, &container_name, lines).await
+}
+
+/// Diagnoses common problems, optionally attempting to fix them.
+#[tauri::command]
+async fn doctor(app: AppHandle, apply_fixes: bool) -> Result<Vec<DoctorCheckResult>, AppError> {
+    // The app state might not exist if setup failed early (e.g. Docker isn't running).
+    let (docker, config, host_port) = match app.try_state::<AppState>() {
+        Some(app_state) => (
+            app_state.docker.clone(),
+            (*app_state.config()).clone(),
+            app_state.host_port(),
+        ),
+
+        None => {
+            let config = utils::get_app_config(&app)?;
+
+            let host_port = utils::get_runtime_state_path(&app)
+                .map(|runtime_state_path| RuntimeState::load(&runtime_state_path).get_host_port())
+                .unwrap_or(container::OPEN_WEBUI_HOST_PORT);
+
+            (docker::connect_docker(&config)?, config, host_port)
+        }
+    };
+
+    let data_dir = utils::get_app_data_dir(&app, &config)?;
+
+    let doctor_ctx = DoctorContext {
+        docker,
+        config,
+        data_dir,
+        host_port,
+    };
+
+    Ok(doctor::run_doctor(doctor_ctx, apply_fixes).await)
+}
+
+/// The profiles defined in the config.
+#[derive(Serialize, Debug, Clone)]
+struct ProfilesInfo {
+    /// The names of the profiles.
+    #[serde(rename = "profiles")]
+    profiles: Vec<String>,
+
+    /// The name of the active profile, if any.
+    #[serde(rename = "activeProfile", skip_serializing_if = "Option::is_none")]
+    active_profile: Option<String>,
+}
+
+/// Lists the profiles defined in the config.
+#[tauri::command]
+fn list_profiles(state: State<'_, AppState>) -> ProfilesInfo {
+    let mut profiles: Vec<String> = state
+        .config()
+        .profiles
+        .as_ref()
+        .map(|profiles| profiles.keys().cloned().collect())
+        .unwrap_or_default();
+
+    profiles.sort();
+
+    ProfilesInfo {
+        profiles,
+        active_profile: state.config().active_profile.clone(),
+    }
+}
+
+/// Switches the active profile and restarts the app to apply it.
+///
+/// The containers are cleaned up on exit and recreated with the new profile on launch.
+/// If the `LOCALLM_PROFILE` environment variable or `--profile` flag is set, it still takes precedence.
+#[tauri::command]
+fn switch_profile(app: AppHandle, name: String) -> Result<(), AppError> {
+    utils::set_active_profile(&app, &name)?;
+
+    app.restart();
+}
+
+/// Removes the containers created by the app, if cleanup on exit is enabled.
+///
+/// # Arguments
+///
+/// * `app_handle` - The app handle.
+fn cleanup_on_exit(app_handle: &AppHandle) {
+    // If the app state was never populated, setup didn't get far enough to create anything.
+    let Some(app_state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+
+    // The containers are reused on the next launch when cleanup on exit is disabled.
+    if !app_state.config().cleanup_on_exit {
+        return;
+    }
+
+    println!("Cleaning up containers, if needed");
+    let cleanup_timeout = Duration::from_secs(app_state.config().cleanup_timeout_secs);
+
+    // A hung Docker daemon shouldn't stop the app from exiting.
+    let cleanup_result = tokio::task::block_in_place(|| {
+        tauri::async_runtime::block_on(async {
+            tokio::time::timeout(
+                cleanup_timeout,
+                container::cleanup_infrastructure(&app_state.docker, &app_state.config(), false),
+            )
+            .await
+        })
+    });
+
+    match cleanup_result {
+        Ok(Ok(_)) => {}
+
+        Ok(Err(container_err)) => utils::show_docker_error(app_handle, &container_err),
+
+        Err(_) => println!(
+            "Warning: Cleanup didn't finish within {} seconds, so it was skipped. It will be cleaned up on the next launch.",
+            cleanup_timeout.as_secs()
+        ),
+    }
+}
+
+/// Gets the context for the `tauri` app.
+fn get_context() -> tauri::Context {
+    tauri::generate_context!()
+}
+
+/// Removes the containers and networks created by the app, then exits without launching the UI.
+///
+/// This is for scripts and uninstallers, so it's run with the `--teardown` flag.
+/// The app isn't built, so it works without a display, and nothing is written to the
+/// app data directory. It refuses to run while the app is running, since that instance
+/// is still using the containers.
+pub async fn teardown() -> Result<(), AppError> {
+    let app_data_dir = utils::resolve_app_local_data_dir(&get_context().config().identifier)?;
+
+    if let Some(pid) = instance::get_running_instance_pid(&app_data_dir).await {
+        return Err(AppError::GenericError(format!(
+            "The app is running (PID {}), so it can't be torn down. Quit it and try again.",
+            pid
+        )));
+    }
+
+    let app_config = utils::read_app_config(&utils::get_app_config_path_in(&app_data_dir)?)?;
+
+    println!("Connecting to Docker");
+    let docker = docker::connect_docker_with_retry(&app_config).await?;
+
+    println!("Removing the containers and networks created by the app");
+    container::cleanup_infrastructure(&docker, &app_config, true).await?;
+
+    println!("Teardown complete");
+
+    Ok(())
+}
+
+/// Prints the config as the app sees it, then exits without launching the UI.
+///
+/// This is the command-line version of `get_effective_config`, so it's run with the
+/// `--print-config` flag. The active profile is merged in and secrets are redacted.
+/// An invalid config is returned as an error, so the app exits with a nonzero code.
+/// The app isn't built, so it works without a display, and nothing is written.
+pub async fn print_config() -> Result<(), AppError> {
+    let app_data_dir = utils::resolve_app_local_data_dir(&get_context().config().identifier)?;
+
+    let app_config = utils::load_app_config_read_only(&app_data_dir).await?;
+
+    let config_yaml =
+        serde_yaml::to_string(&app_config.redacted()).map_err(|e| AppError::YamlError(e))?;
+
+    print!("{}", config_yaml);
+
     Ok(())
 }
 
@@ -114,32 +1468,69 @@ pub async fn run() -> Result<(), AppError> {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
-        .invoke_handler(tauri::generate_handler![setup_complete])
+        .manage(ActivePulls::default())
+        .manage(SetupHistory::default())
+        .invoke_handler(tauri::generate_handler![
+            setup_complete,
+            get_webui_url,
+            get_app_info,
+            get_notice,
+            get_effective_config,
+            validate_config,
+            is_webui_ready,
+            list_models,
+            get_container_statuses,
+            get_port_mappings,
+            get_stack_health,
+            get_docker_api_version,
+            get_managed_resources,
+            exec_in_container,
+            get_recent_logs,
+            recreate_service,
+            purge_service,
+            pause_stack,
+            set_host_port,
+            unpause_stack,
+            set_openwebui_tag,
+            cancel_pull,
+            get_setup_history,
+            reconcile,
+            backup_data,
+            restore_data,
+            vacuum_database,
+            doctor,
+            check_images_present,
+            import_image,
+            get_disk_usage,
+            list_profiles,
+            switch_profile
+        ])
         .setup(move |app| {
             spawn(setup(app.handle().clone()));
 
             Ok(())
         })
-        .build(tauri::generate_context!())
+        .build(get_context())
         .map_err(|e| AppError::TauriError(e))?;
 
     // Run the app.
     #[allow(unused_variables)]
     app.run(move |app_handle, event| match event {
         RunEvent::Exit => {
-            // On exit, remove the containers and networks created.
-            println!("Cleaning up containers, if needed");
-            let cleanup_result = tokio::task::block_in_place(|| {
-                let app_config = utils::get_app_config(&app_handle).unwrap();
-
-                tauri::async_runtime::block_on(async {
-                    container::cleanup_infrastructure(&app_config).await
-                })
-            });
-
-            if let Err(container_err) = cleanup_result {
-                utils::show_docker_error(&app_handle, &container_err);
+            // A pull that's still running would otherwise hold up the cleanup.
+            if let Some(app_state) = app_handle.try_state::<AppState>() {
+                app_state.cancel_prewarm_task();
             }
+
+            // This includes pulls made before the `AppState` exists, like the setup's pulls.
+            if let Some(active_pulls) = app_handle.try_state::<ActivePulls>() {
+                active_pulls.cancel(None);
+            }
+
+            // The lock is released after the cleanup, so a new instance can't start
+            // creating containers while the old ones are still being removed.
+            cleanup_on_exit(app_handle);
+            instance::release_instance_lock(app_handle);
         }
         _ => {}
     });
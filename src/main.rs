@@ -8,6 +8,16 @@ use locallm_chat_lib::error::AppError;
 async fn main() -> Result<(), AppError> {
     tauri::async_runtime::set(tokio::runtime::Handle::current());
 
+    // Tear down what the app created and exit, for scripts and uninstallers.
+    if locallm_chat_lib::utils::is_teardown_requested() {
+        return locallm_chat_lib::teardown().await;
+    }
+
+    // Print the resolved config and exit, for scripts and support sessions.
+    if locallm_chat_lib::utils::is_print_config_requested() {
+        return locallm_chat_lib::print_config().await;
+    }
+
     let _ = locallm_chat_lib::run().await?;
 
     Ok(())
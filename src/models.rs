@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest;
+
+use crate::{
+    config::LlmChatConfig,
+    container,
+    error::AppError,
+    events::{MODEL_PRELOAD_EVENT, ModelPreloadProgress, ModelPreloadStatus},
+};
+
+/// Preloads the `preload_models` in the Ollama extra backend service, emitting `model-preload` events.
+///
+/// Each model is pulled with `ollama pull` and then loaded into memory with `ollama run`,
+/// through the exec flow, so the Ollama service doesn't need to be reachable from the host.
+/// Failures are logged as warnings and don't stop the other models from being preloaded.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn preload_models(app: AppHandle, docker: Docker, app_config: LlmChatConfig) {
+    let Some(preload_models) = app_config.preload_models.clone() else {
+        return;
+    };
+
+    let has_ollama_service = app_config
+        .get_enabled_extra_services()
+        .iter()
+        .any(|extra_service| extra_service.name == app_config.ollama_service);
+
+    if !has_ollama_service {
+        println!(
+            "Warning: Skipping preloading models, since there's no enabled '{}' service",
+            app_config.ollama_service
+        );
+
+        return;
+    }
+
+    let container_name = container::get_container_name(&app_config.ollama_service);
+
+    for model in preload_models {
+        let status = match preload_model(&app, &docker, &container_name, &model).await {
+            Ok(_) => {
+                println!("Preloaded model '{}'", model);
+
+                ModelPreloadProgress {
+                    model,
+                    status: ModelPreloadStatus::Ready,
+                    error: None,
+                }
+            }
+
+            Err(err) => {
+                println!("Warning: Failed to preload model '{}': {}", model, err);
+
+                ModelPreloadProgress {
+                    model,
+                    status: ModelPreloadStatus::Failed,
+                    error: Some(err.to_string()),
+                }
+            }
+        };
+
+        let _ = app.emit(MODEL_PRELOAD_EVENT, status);
+    }
+}
+
+/// Pulls a model and loads it into memory in the Ollama container.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the Ollama container.
+/// * `model` - The name of the model.
+async fn preload_model(
+    app: &AppHandle,
+    docker: &Docker,
+    container_name: &str,
+    model: &str,
+) -> Result<(), AppError> {
+    emit_preload_status(app, model, ModelPreloadStatus::Pulling);
+    run_ollama_command(docker, container_name, vec!["pull", model]).await?;
+
+    // Running the model with an empty prompt loads it without generating anything.
+    emit_preload_status(app, model, ModelPreloadStatus::Loading);
+    run_ollama_command(docker, container_name, vec!["run", model, ""]).await
+}
+
+/// Runs an `ollama` command in the Ollama container, erroring if it doesn't exit with `0`.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `container_name` - The name of the Ollama container.
+/// * `args` - The args to pass to `ollama`.
+async fn run_ollama_command(
+    docker: &Docker,
+    container_name: &str,
+    args: Vec<&str>,
+) -> Result<(), AppError> {
+    let cmd = std::iter::once("ollama")
+        .chain(args)
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+
+    let exec_result = container::exec_in_container(docker, container_name, cmd.clone()).await?;
+
+    match exec_result.exit_code {
+        Some(0) => Ok(()),
+
+        exit_code => Err(AppError::GenericError(format!(
+            "'{}' exited with {}: {}",
+            cmd.join(" "),
+            exit_code
+                .map(|exit_code| format!("code {}", exit_code))
+                .unwrap_or_else(|| "an unknown exit code".to_string()),
+            exec_result.stderr.trim()
+        ))),
+    }
+}
+
+/// Emits the stage a model being preloaded is in.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `model` - The name of the model.
+/// * `status` - The stage the model is in.
+fn emit_preload_status(app: &AppHandle, model: &str, status: ModelPreloadStatus) {
+    let _ = app.emit(
+        MODEL_PRELOAD_EVENT,
+        ModelPreloadProgress {
+            model: model.to_string(),
+            status,
+            error: None,
+        },
+    );
+}
+
+/// How long to wait for Open WebUI to list its models.
+const LIST_MODELS_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A model available in Open WebUI.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ModelInfo {
+    /// The ID of the model (e.g. `llama3.2:latest`).
+    #[serde(rename = "id")]
+    pub id: String,
+
+    /// The display name of the model.
+    #[serde(rename = "name", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// What provides the model (e.g. `ollama` or `openai`).
+    #[serde(rename = "owned_by", default, skip_serializing_if = "Option::is_none")]
+    pub owned_by: Option<String>,
+
+    /// When the model was created, as a Unix timestamp.
+    #[serde(rename = "created", default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<i64>,
+}
+
+/// The response from Open WebUI's `/api/models` endpoint.
+#[derive(Deserialize, Debug, Clone)]
+struct ModelsResponse {
+    /// The available models.
+    #[serde(rename = "data")]
+    data: Vec<ModelInfo>,
+}
+
+/// Lists the models available in Open WebUI.
+///
+/// When `openwebui_api_key` is set, it's sent as a bearer token, which is needed
+/// when Open WebUI's authentication is enabled.
+///
+/// # Arguments
+///
+/// * `webui_url` - The base URL for Open WebUI.
+/// * `api_key` - The Open WebUI API key, if any.
+pub async fn list_models(
+    webui_url: &str,
+    api_key: Option<&str>,
+) -> Result<Vec<ModelInfo>, AppError> {
+    let client = reqwest::Client::builder()
+        .timeout(LIST_MODELS_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::GenericError(format!("Failed to create the HTTP client: {}", e)))?;
+
+    let mut request = client.get(format!("{}/api/models", webui_url));
+
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| AppError::GenericError(format!("Failed to list the models: {}", e)))?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED
+        || response.status() == reqwest::StatusCode::FORBIDDEN
+    {
+        return Err(AppError::ConfigError(
+            "Open WebUI requires authentication to list the models, so set `openwebui_api_key`"
+                .to_string(),
+        ));
+    }
+
+    let models_response = response
+        .error_for_status()
+        .map_err(|e| AppError::GenericError(format!("Failed to list the models: {}", e)))?
+        .json::<ModelsResponse>()
+        .await
+        .map_err(|e| AppError::GenericError(format!("Failed to parse the models: {}", e)))?;
+
+    Ok(models_response.data)
+}
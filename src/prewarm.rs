@@ -0,0 +1,88 @@
+use bollard::Docker;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    config::LlmChatConfig,
+    container, docker,
+    error::AppError,
+    events::{ImagePrewarmFailure, PREWARM_IMAGES_COMPLETE_EVENT, PrewarmImagesComplete},
+    progress::TauriProgressReporter,
+    state::ActivePulls,
+};
+
+/// Pulls the `prewarm_images` in the background, emitting `prewarm-images-complete` when done.
+///
+/// Images are pulled one at a time, so the pulls don't compete with the running stack
+/// for bandwidth. Failures are logged as warnings and don't stop the other images from
+/// being pulled.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn prewarm_images(app: AppHandle, docker: Docker, app_config: LlmChatConfig) {
+    let Some(prewarm_images) = app_config.prewarm_images.clone() else {
+        return;
+    };
+
+    let reporter = TauriProgressReporter::new(app.clone());
+    let active_pulls = app.state::<ActivePulls>();
+    let mut prewarm_complete = PrewarmImagesComplete::default();
+
+    for image in prewarm_images {
+        match prewarm_image(&reporter, &docker, &app_config, &image, &active_pulls).await {
+            Ok(true) => {
+                println!("Pre-warmed image '{}'", image);
+
+                prewarm_complete.pulled_images.push(image);
+            }
+
+            Ok(false) => prewarm_complete.cached_images.push(image),
+
+            Err(err) => {
+                println!("Warning: Failed to pre-warm image '{}': {}", image, err);
+
+                prewarm_complete.failed_images.push(ImagePrewarmFailure {
+                    image,
+                    error: err.to_string(),
+                });
+            }
+        }
+    }
+
+    let _ = app.emit(PREWARM_IMAGES_COMPLETE_EVENT, prewarm_complete);
+}
+
+/// Pulls an image, unless it's already present.
+///
+/// Returns `true` if the image was pulled.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the progress of the pull.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+/// * `image` - The image to pull.
+/// * `active_pulls` - Where the pull is tracked, so it can be cancelled.
+async fn prewarm_image(
+    reporter: &TauriProgressReporter,
+    docker: &Docker,
+    app_config: &LlmChatConfig,
+    image: &str,
+    active_pulls: &ActivePulls,
+) -> Result<bool, AppError> {
+    match docker.inspect_image(image).await {
+        Ok(_) => return Ok(false),
+
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => {}
+
+        Err(err) => return Err(docker::map_docker_error(docker, err)),
+    }
+
+    container::pull_optional_image(reporter, docker, app_config, image, active_pulls).await?;
+
+    Ok(true)
+}
@@ -0,0 +1,519 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    error::AppError,
+    events::{
+        AppEventKind, HEALTH_CHECK_EVENT, HealthCheckAttempt, IMAGE_LOAD_PROGRESS_EVENT,
+        ImageLoadProgress, PULL_PROGRESS_EVENT, PullProgress,
+        SETUP_ERROR_EVENT, SETUP_LOG_EVENT, SETUP_PHASE_EVENT, SETUP_RETRY_EVENT, SetupError,
+        SetupLog, SetupPhaseChanged, SetupProgressPhase, SetupRetry, TOTAL_PULL_PROGRESS_EVENT,
+        TotalPullProgress,
+    },
+    state::SetupHistory,
+};
+
+/// Receives progress from the setup and container functions.
+///
+/// This keeps the orchestration logic independent of Tauri, so the same functions can
+/// report to the frontend when the app is running and to a `RecordingProgressReporter`
+/// anywhere else.
+pub trait ProgressReporter: Send + Sync {
+    /// Reports that setup has moved on to a new phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - The phase that started.
+    fn phase(&self, phase: SetupProgressPhase);
+
+    /// Reports the progress of an image pull.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The progress of the pull.
+    fn progress(&self, progress: PullProgress);
+
+    /// Reports the progress across all of the images being pulled.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The progress across all of the pulls.
+    fn total_pull_progress(&self, progress: TotalPullProgress);
+
+    /// Reports the progress of loading images from an archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The progress of the load.
+    fn image_load_progress(&self, progress: ImageLoadProgress);
+
+    /// Reports the result of an attempt at checking whether Open WebUI is healthy.
+    ///
+    /// # Arguments
+    ///
+    /// * `health_check` - The result of the attempt.
+    fn health_check(&self, health_check: HealthCheckAttempt);
+
+    /// Reports a log message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to log.
+    fn log(&self, message: &str);
+
+    /// Reports an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error that occurred.
+    fn error(&self, error: &AppError);
+
+    /// Reports that setup failed with a transient error and is being retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry` - The attempt that's starting, and the error that failed the previous one.
+    fn retry(&self, retry: SetupRetry);
+}
+
+/// Reports progress to the frontend by emitting Tauri events.
+///
+/// Log messages and errors are also printed to stdout, and every event is recorded
+/// in the `SetupHistory`.
+pub struct TauriProgressReporter {
+    /// The app handle to emit the events with.
+    app: AppHandle,
+}
+
+impl TauriProgressReporter {
+    /// Creates a reporter that emits events with the app handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - The app handle.
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+
+    /// Records an event in the setup history.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The event and its payload.
+    fn record(&self, kind: AppEventKind) {
+        if let Some(setup_history) = self.app.try_state::<SetupHistory>() {
+            setup_history.record(kind);
+        }
+    }
+}
+
+impl ProgressReporter for TauriProgressReporter {
+    fn phase(&self, phase: SetupProgressPhase) {
+        let setup_phase = SetupPhaseChanged { phase };
+
+        let _ = self.app.emit(SETUP_PHASE_EVENT, setup_phase.clone());
+        self.record(AppEventKind::SetupPhase(setup_phase));
+    }
+
+    fn progress(&self, progress: PullProgress) {
+        let _ = self.app.emit(PULL_PROGRESS_EVENT, progress.clone());
+        self.record(AppEventKind::PullProgress(progress));
+    }
+
+    fn total_pull_progress(&self, progress: TotalPullProgress) {
+        let _ = self.app.emit(TOTAL_PULL_PROGRESS_EVENT, progress.clone());
+        self.record(AppEventKind::TotalPullProgress(progress));
+    }
+
+    fn image_load_progress(&self, progress: ImageLoadProgress) {
+        let _ = self.app.emit(IMAGE_LOAD_PROGRESS_EVENT, progress.clone());
+        self.record(AppEventKind::ImageLoadProgress(progress));
+    }
+
+    fn health_check(&self, health_check: HealthCheckAttempt) {
+        let _ = self.app.emit(HEALTH_CHECK_EVENT, health_check.clone());
+        self.record(AppEventKind::HealthCheck(health_check));
+    }
+
+    fn log(&self, message: &str) {
+        println!("{}", message);
+
+        let setup_log = SetupLog {
+            message: message.to_string(),
+        };
+
+        let _ = self.app.emit(SETUP_LOG_EVENT, setup_log.clone());
+        self.record(AppEventKind::SetupLog(setup_log));
+    }
+
+    fn error(&self, error: &AppError) {
+        println!("Error: {}", error);
+
+        let setup_error = SetupError {
+            category: error.category(),
+            message: error.to_string(),
+            retryable: error.is_retryable(),
+        };
+
+        let _ = self.app.emit(SETUP_ERROR_EVENT, setup_error.clone());
+        self.record(AppEventKind::SetupError(setup_error));
+    }
+
+    fn retry(&self, retry: SetupRetry) {
+        println!(
+            "Retrying setup ({}/{}) after: {}",
+            retry.attempt, retry.max_attempts, retry.message
+        );
+
+        let _ = self.app.emit(SETUP_RETRY_EVENT, retry.clone());
+        self.record(AppEventKind::SetupRetry(retry));
+    }
+}
+
+/// Sums the progress of several image pulls, reporting it as `total_pull_progress`
+/// along with forwarding everything to another reporter.
+///
+/// Registries don't report an image's size up front, so the total grows as each layer
+/// reports its size. It's only known once every image has started and every layer has
+/// reported a size.
+pub struct PullProgressAccumulator<'a> {
+    /// The reporter to forward the progress to.
+    reporter: &'a dyn ProgressReporter,
+
+    /// The number of images being pulled.
+    images_total: usize,
+
+    /// The latest progress of each image, keyed by the requested image rather than any
+    /// registry mirror it's pulled from.
+    images_progress: Mutex<HashMap<String, PullProgress>>,
+}
+
+impl<'a> PullProgressAccumulator<'a> {
+    /// Creates an accumulator that forwards the progress to another reporter.
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - The reporter to forward the progress to.
+    /// * `images_total` - The number of images being pulled.
+    pub fn new(reporter: &'a dyn ProgressReporter, images_total: usize) -> Self {
+        Self {
+            reporter,
+            images_total,
+            images_progress: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl ProgressReporter for PullProgressAccumulator<'_> {
+    fn phase(&self, phase: SetupProgressPhase) {
+        self.reporter.phase(phase);
+    }
+
+    fn progress(&self, progress: PullProgress) {
+        let total_pull_progress = self.images_progress.lock().ok().map(|mut images_progress| {
+            images_progress.insert(progress.image.clone(), progress.clone());
+
+            let images_started = images_progress.len();
+            let images = images_progress.values();
+
+            TotalPullProgress {
+                downloaded_bytes: images.clone().map(|image| image.downloaded_bytes).sum(),
+                total_bytes: images.clone().map(|image| image.total_bytes).sum(),
+                images_started,
+                images_total: self.images_total,
+                is_total_known: images_started >= self.images_total
+                    && images_progress.values().all(|image| image.indeterminate_layers == 0),
+            }
+        });
+
+        self.reporter.progress(progress);
+
+        if let Some(total_pull_progress) = total_pull_progress {
+            self.reporter.total_pull_progress(total_pull_progress);
+        }
+    }
+
+    fn total_pull_progress(&self, progress: TotalPullProgress) {
+        self.reporter.total_pull_progress(progress);
+    }
+
+    fn image_load_progress(&self, progress: ImageLoadProgress) {
+        self.reporter.image_load_progress(progress);
+    }
+
+    fn health_check(&self, health_check: HealthCheckAttempt) {
+        self.reporter.health_check(health_check);
+    }
+
+    fn log(&self, message: &str) {
+        self.reporter.log(message);
+    }
+
+    fn error(&self, error: &AppError) {
+        self.reporter.error(error);
+    }
+
+    fn retry(&self, retry: SetupRetry) {
+        self.reporter.retry(retry);
+    }
+}
+
+/// A progress update recorded by a `RecordingProgressReporter`.
+#[derive(Debug, Clone)]
+pub enum RecordedProgress {
+    /// A phase started.
+    Phase(SetupProgressPhase),
+
+    /// An image pull made progress.
+    Progress(PullProgress),
+
+    /// The pulls made progress across all of the images.
+    TotalPullProgress(TotalPullProgress),
+
+    /// Loading images from an archive made progress.
+    ImageLoadProgress(ImageLoadProgress),
+
+    /// Open WebUI's health was checked.
+    HealthCheck(HealthCheckAttempt),
+
+    /// A message was logged.
+    Log(String),
+
+    /// An error occurred, with its message.
+    Error(String),
+
+    /// Setup is being retried.
+    Retry(SetupRetry),
+}
+
+/// Records progress in memory, in the order it was reported.
+///
+/// This is for asserting on the sequence of progress without a running app.
+#[derive(Debug, Default)]
+pub struct RecordingProgressReporter {
+    /// The progress reported so far.
+    recorded: Mutex<Vec<RecordedProgress>>,
+}
+
+impl RecordingProgressReporter {
+    /// Gets the progress reported so far.
+    pub fn recorded(&self) -> Vec<RecordedProgress> {
+        self.recorded
+            .lock()
+            .map(|recorded| recorded.clone())
+            .unwrap_or_default()
+    }
+
+    /// Gets the phases reported so far, in order.
+    pub fn phases(&self) -> Vec<SetupProgressPhase> {
+        self.recorded()
+            .into_iter()
+            .filter_map(|recorded| match recorded {
+                RecordedProgress::Phase(phase) => Some(phase),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Records a progress update.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress` - The progress update to record.
+    fn record(&self, progress: RecordedProgress) {
+        if let Ok(mut recorded) = self.recorded.lock() {
+            recorded.push(progress);
+        }
+    }
+}
+
+impl ProgressReporter for RecordingProgressReporter {
+    fn phase(&self, phase: SetupProgressPhase) {
+        self.record(RecordedProgress::Phase(phase));
+    }
+
+    fn progress(&self, progress: PullProgress) {
+        self.record(RecordedProgress::Progress(progress));
+    }
+
+    fn total_pull_progress(&self, progress: TotalPullProgress) {
+        self.record(RecordedProgress::TotalPullProgress(progress));
+    }
+
+    fn image_load_progress(&self, progress: ImageLoadProgress) {
+        self.record(RecordedProgress::ImageLoadProgress(progress));
+    }
+
+    fn health_check(&self, health_check: HealthCheckAttempt) {
+        self.record(RecordedProgress::HealthCheck(health_check));
+    }
+
+    fn log(&self, message: &str) {
+        self.record(RecordedProgress::Log(message.to_string()));
+    }
+
+    fn error(&self, error: &AppError) {
+        self.record(RecordedProgress::Error(error.to_string()));
+    }
+
+    fn retry(&self, retry: SetupRetry) {
+        self.record(RecordedProgress::Retry(retry));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_pull_progress(image: &str, downloaded_bytes: i64, total_bytes: i64) -> PullProgress {
+        PullProgress {
+            image: image.to_string(),
+            layer_id: Some("a1b2c3".to_string()),
+            status: Some("Downloading".to_string()),
+            downloaded_bytes,
+            total_bytes,
+            indeterminate_layers: 0,
+        }
+    }
+
+    fn get_total_pull_progress(reporter: &RecordingProgressReporter) -> Vec<TotalPullProgress> {
+        reporter
+            .recorded()
+            .into_iter()
+            .filter_map(|recorded| match recorded {
+                RecordedProgress::TotalPullProgress(progress) => Some(progress),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_setup_phases_in_order() {
+        let recording_reporter = RecordingProgressReporter::default();
+        let reporter = PullProgressAccumulator::new(&recording_reporter, 1);
+
+        reporter.phase(SetupProgressPhase::PullingImages);
+        reporter.phase(SetupProgressPhase::CreatingInfrastructure);
+        reporter.phase(SetupProgressPhase::RunningInitCommand);
+        reporter.phase(SetupProgressPhase::WaitingForOpenWebUi);
+        reporter.phase(SetupProgressPhase::Ready);
+
+        assert_eq!(
+            recording_reporter.phases(),
+            vec![
+                SetupProgressPhase::PullingImages,
+                SetupProgressPhase::CreatingInfrastructure,
+                SetupProgressPhase::RunningInitCommand,
+                SetupProgressPhase::WaitingForOpenWebUi,
+                SetupProgressPhase::Ready,
+            ]
+        );
+    }
+
+    #[test]
+    fn forwards_each_pull_progress_before_the_total() {
+        let recording_reporter = RecordingProgressReporter::default();
+        let reporter = PullProgressAccumulator::new(&recording_reporter, 2);
+
+        reporter.phase(SetupProgressPhase::PullingImages);
+        reporter.progress(build_pull_progress("ollama/ollama:latest", 10, 100));
+        reporter.log("Pulled 'ollama/ollama:latest'");
+
+        let recorded = recording_reporter.recorded();
+        assert_eq!(recorded.len(), 4);
+        assert!(matches!(recorded[0], RecordedProgress::Phase(SetupProgressPhase::PullingImages)));
+        assert!(matches!(
+            &recorded[1],
+            RecordedProgress::Progress(progress) if progress.image == "ollama/ollama:latest"
+        ));
+        assert!(matches!(
+            &recorded[2],
+            RecordedProgress::TotalPullProgress(progress) if progress.images_started == 1
+        ));
+        assert!(matches!(
+            &recorded[3],
+            RecordedProgress::Log(message) if message == "Pulled 'ollama/ollama:latest'"
+        ));
+    }
+
+    #[test]
+    fn sums_the_latest_progress_of_each_image() {
+        let recording_reporter = RecordingProgressReporter::default();
+        let reporter = PullProgressAccumulator::new(&recording_reporter, 2);
+
+        reporter.progress(build_pull_progress("ollama/ollama:latest", 10, 100));
+        reporter.progress(build_pull_progress("apache/tika:latest", 5, 50));
+        reporter.progress(build_pull_progress("ollama/ollama:latest", 40, 100));
+
+        let total_pull_progress = get_total_pull_progress(&recording_reporter);
+        assert_eq!(total_pull_progress.len(), 3);
+
+        let latest = total_pull_progress.last().unwrap();
+        assert_eq!(latest.downloaded_bytes, 45);
+        assert_eq!(latest.total_bytes, 150);
+        assert_eq!(latest.images_started, 2);
+        assert_eq!(latest.images_total, 2);
+    }
+
+    #[test]
+    fn restarted_pull_replaces_the_image_progress() {
+        let recording_reporter = RecordingProgressReporter::default();
+        let reporter = PullProgressAccumulator::new(&recording_reporter, 1);
+
+        // A mirror fails partway through, then the pull falls back to the registry.
+        reporter.progress(build_pull_progress("ollama/ollama:latest", 60, 100));
+        reporter.progress(build_pull_progress("ollama/ollama:latest", 5, 100));
+
+        let latest = get_total_pull_progress(&recording_reporter).pop().unwrap();
+        assert_eq!(latest.downloaded_bytes, 5);
+        assert_eq!(latest.total_bytes, 100);
+        assert_eq!(latest.images_started, 1);
+    }
+
+    #[test]
+    fn total_is_known_once_every_image_and_layer_has_a_size() {
+        let recording_reporter = RecordingProgressReporter::default();
+        let reporter = PullProgressAccumulator::new(&recording_reporter, 2);
+
+        reporter.progress(build_pull_progress("ollama/ollama:latest", 10, 100));
+
+        let mut tika_progress = build_pull_progress("apache/tika:latest", 0, 0);
+        tika_progress.indeterminate_layers = 1;
+        reporter.progress(tika_progress);
+
+        reporter.progress(build_pull_progress("apache/tika:latest", 5, 50));
+
+        let is_total_known: Vec<bool> = get_total_pull_progress(&recording_reporter)
+            .iter()
+            .map(|progress| progress.is_total_known)
+            .collect();
+        assert_eq!(is_total_known, vec![false, false, true]);
+    }
+
+    #[test]
+    fn forwards_errors_and_retries() {
+        let recording_reporter = RecordingProgressReporter::default();
+        let reporter = PullProgressAccumulator::new(&recording_reporter, 1);
+
+        let error = AppError::ImagePullCancelled("ollama/ollama:latest".to_string());
+        reporter.error(&error);
+        reporter.retry(SetupRetry {
+            attempt: 2,
+            max_attempts: 3,
+            message: error.to_string(),
+        });
+
+        let recorded = recording_reporter.recorded();
+        assert_eq!(recorded.len(), 2);
+        assert!(matches!(
+            &recorded[0],
+            RecordedProgress::Error(message)
+                if *message == error.to_string()
+        ));
+        assert!(matches!(
+            &recorded[1],
+            RecordedProgress::Retry(retry) if retry.attempt == 2 && retry.max_attempts == 3
+        ));
+    }
+}
@@ -0,0 +1,438 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::Path,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicU16, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bollard::Docker;
+use futures_util::future::{AbortHandle, AbortRegistration};
+use serde::{Deserialize, Serialize};
+use tauri::async_runtime::JoinHandle;
+
+use crate::{
+    config::LlmChatConfig,
+    container,
+    error::AppError,
+    events::{AppEvent, AppEventKind},
+};
+
+/// Runtime state for the app.
+///
+/// This is populated once during setup and managed by Tauri,
+/// so commands can access it with `tauri::State<AppState>`.
+pub struct AppState {
+    /// The loaded config for the app.
+    ///
+    /// This can be changed at runtime (e.g. switching Open WebUI's image tag),
+    /// so use `config()` to read it.
+    pub config: RwLock<Arc<LlmChatConfig>>,
+
+    /// The port on the host that Open WebUI is bound to.
+    ///
+    /// This can be changed at runtime, so use `host_port()` to read it.
+    pub host_port: AtomicU16,
+
+    /// The Docker client.
+    pub docker: Docker,
+
+    /// The background task pulling the `prewarm_images`, so it can be cancelled on exit.
+    pub prewarm_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AppState {
+    /// Creates the app state.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The loaded config for the app.
+    /// * `host_port` - The port on the host that Open WebUI is bound to.
+    /// * `docker` - The Docker client.
+    pub fn new(config: LlmChatConfig, host_port: u16, docker: Docker) -> Self {
+        Self {
+            config: RwLock::new(Arc::new(config)),
+            host_port: AtomicU16::new(host_port),
+            docker,
+            prewarm_task: Mutex::new(None),
+        }
+    }
+
+    /// Gets the loaded config for the app.
+    ///
+    /// This is a snapshot, so it isn't affected by later changes to the config.
+    pub fn config(&self) -> Arc<LlmChatConfig> {
+        match self.config.read() {
+            Ok(config) => config.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Replaces the loaded config for the app.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The new config.
+    pub fn set_config(&self, config: LlmChatConfig) {
+        match self.config.write() {
+            Ok(mut current_config) => *current_config = Arc::new(config),
+            Err(poisoned) => *poisoned.into_inner() = Arc::new(config),
+        }
+    }
+
+    /// Gets the port on the host that Open WebUI is bound to.
+    pub fn host_port(&self) -> u16 {
+        self.host_port.load(Ordering::SeqCst)
+    }
+
+    /// Sets the port on the host that Open WebUI is bound to.
+    ///
+    /// # Arguments
+    ///
+    /// * `host_port` - The new host port.
+    pub fn set_host_port(&self, host_port: u16) {
+        self.host_port.store(host_port, Ordering::SeqCst);
+    }
+
+    /// Gets the URL for Open WebUI.
+    pub fn webui_url(&self) -> String {
+        format!(
+            "http://localhost:{}{}",
+            self.host_port(),
+            self.config().get_base_path()
+        )
+    }
+
+    /// Cancels the background task pulling the `prewarm_images`, if it's still running.
+    pub fn cancel_prewarm_task(&self) {
+        let prewarm_task = self
+            .prewarm_task
+            .lock()
+            .ok()
+            .and_then(|mut prewarm_task| prewarm_task.take());
+
+        if let Some(prewarm_task) = prewarm_task {
+            println!("Cancelling pre-warming images");
+            prewarm_task.abort();
+        }
+    }
+}
+
+/// The image pulls in progress, so they can be cancelled.
+///
+/// This is managed by Tauri separately from `AppState`, so pulls made
+/// before the `AppState` exists (like the engine self-test) can be cancelled too.
+#[derive(Default)]
+pub struct ActivePulls {
+    /// The handle for aborting each pull, keyed by the image being pulled.
+    pulls: Mutex<HashMap<String, AbortHandle>>,
+}
+
+impl ActivePulls {
+    /// Tracks a pull, returning the registration to make the pull abortable with.
+    ///
+    /// The pull stops being tracked when the returned guard is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image being pulled.
+    pub fn track(&self, image: &str) -> (ActivePullGuard<'_>, AbortRegistration) {
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+
+        if let Ok(mut pulls) = self.pulls.lock() {
+            pulls.insert(image.to_string(), abort_handle);
+        }
+
+        let guard = ActivePullGuard {
+            active_pulls: self,
+            image: image.to_string(),
+        };
+
+        (guard, abort_registration)
+    }
+
+    /// Cancels the pulls in progress, returning the images whose pulls were cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to cancel the pull of, or `None` to cancel every pull.
+    pub fn cancel(&self, image: Option<&str>) -> Vec<String> {
+        let Ok(mut pulls) = self.pulls.lock() else {
+            return Vec::new();
+        };
+
+        let cancelled_images = pulls
+            .keys()
+            .filter(|pulled_image| image.is_none_or(|image| image == pulled_image.as_str()))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        for cancelled_image in &cancelled_images {
+            if let Some(abort_handle) = pulls.remove(cancelled_image) {
+                abort_handle.abort();
+            }
+        }
+
+        cancelled_images
+    }
+}
+
+/// Stops tracking a pull when it's dropped, whether the pull finished, failed, or was cancelled.
+pub struct ActivePullGuard<'a> {
+    /// The pulls the pull is tracked in.
+    active_pulls: &'a ActivePulls,
+
+    /// The image being pulled.
+    image: String,
+}
+
+impl Drop for ActivePullGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut pulls) = self.active_pulls.pulls.lock() {
+            pulls.remove(&self.image);
+        }
+    }
+}
+
+/// The maximum number of events kept in the setup history.
+pub const SETUP_HISTORY_CAPACITY: usize = 500;
+
+/// The setup events emitted so far, so the frontend can rebuild the timeline
+/// if it reloads or starts listening late.
+///
+/// This is a ring buffer, so the oldest events are dropped once it holds
+/// `SETUP_HISTORY_CAPACITY` events. Progress updates replace the previous update
+/// for the same image or archive, so a long pull doesn't push out everything else.
+#[derive(Default)]
+pub struct SetupHistory {
+    /// The recorded events, oldest first.
+    events: Mutex<VecDeque<AppEvent>>,
+}
+
+impl SetupHistory {
+    /// Records an event that was emitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The event and its payload.
+    pub fn record(&self, kind: AppEventKind) {
+        let Ok(mut events) = self.events.lock() else {
+            return;
+        };
+
+        if let Some(progress_key) = kind.progress_key() {
+            events.retain(|event| event.kind.progress_key().as_ref() != Some(&progress_key));
+        }
+
+        while events.len() >= SETUP_HISTORY_CAPACITY {
+            events.pop_front();
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+
+        events.push_back(AppEvent { timestamp_ms, kind });
+    }
+
+    /// Gets the recorded events, oldest first.
+    pub fn events(&self) -> Vec<AppEvent> {
+        self.events
+            .lock()
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// State that's persisted between launches of the app.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct RuntimeState {
+    /// The local ID of each required image, keyed by the image name and tag.
+    #[serde(rename = "imageIds", default)]
+    pub image_ids: HashMap<String, String>,
+
+    /// The IDs of images that were replaced by a newer pull and can be pruned
+    /// once no container uses them.
+    #[serde(rename = "imagesToPrune", default)]
+    pub images_to_prune: Vec<String>,
+
+    /// The setup phases that completed during a setup that hasn't finished yet.
+    ///
+    /// This is cleared once setup finishes, so it's only non-empty when
+    /// a previous launch failed partway through setup.
+    #[serde(rename = "completedSetupPhases", default)]
+    pub completed_setup_phases: Vec<SetupPhase>,
+
+    /// The host port Open WebUI was moved to with the `set_host_port` command, if any.
+    #[serde(rename = "hostPort", default, skip_serializing_if = "Option::is_none")]
+    pub host_port: Option<u16>,
+
+    /// The host paths of each extra backend service's volume bindings that were created
+    /// for it, keyed by the service name, so they can be removed with `purge_service`.
+    ///
+    /// Paths that already existed before the service was created aren't included,
+    /// since they belong to the user.
+    #[serde(rename = "serviceVolumePaths", default)]
+    pub service_volume_paths: HashMap<String, Vec<String>>,
+
+    /// The named volumes of each extra backend service that were created for it,
+    /// keyed by the service name, so they can be removed with `purge_service`.
+    ///
+    /// Volumes that already existed before the service was created aren't included,
+    /// since they belong to the user.
+    #[serde(rename = "serviceVolumes", default)]
+    pub service_volumes: HashMap<String, Vec<String>>,
+}
+
+/// A phase of setup that can be skipped when resuming a failed setup.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum SetupPhase {
+    /// The required images were pulled.
+    #[serde(rename = "imagesPulled")]
+    ImagesPulled,
+
+    /// The networks and containers were created.
+    #[serde(rename = "infrastructureReady")]
+    InfrastructureReady,
+}
+
+impl RuntimeState {
+    /// Gets the port on the host to bind Open WebUI to.
+    ///
+    /// This is the port it was last moved to, or the default port if it hasn't been moved.
+    pub fn get_host_port(&self) -> u16 {
+        self.host_port.unwrap_or(container::OPEN_WEBUI_HOST_PORT)
+    }
+
+    /// Loads the runtime state from a file.
+    ///
+    /// If the file doesn't exist or can't be read, the default state is returned,
+    /// since losing the state shouldn't stop the app from starting.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the runtime state file.
+    pub fn load(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+
+        let runtime_state = fs::read_to_string(path)
+            .map_err(|e| AppError::IOError(e))
+            .and_then(|contents| {
+                serde_json::from_str::<Self>(&contents)
+                    .map_err(|e| AppError::GenericError(format!("Failed to parse runtime state: {}", e)))
+            });
+
+        match runtime_state {
+            Ok(runtime_state) => runtime_state,
+
+            Err(err) => {
+                println!("Failed to load runtime state, starting fresh: {}", err);
+
+                Self::default()
+            }
+        }
+    }
+
+    /// Records the host paths and named volumes of the enabled extra backend services'
+    /// volume bindings that don't exist yet, since Docker creates them for the service
+    /// when its container is created.
+    ///
+    /// # Arguments
+    ///
+    /// * `docker` - The Docker client.
+    /// * `app_config` - The application configuration.
+    pub async fn track_service_volumes(
+        &mut self,
+        docker: &Docker,
+        app_config: &LlmChatConfig,
+    ) -> Result<(), AppError> {
+        for extra_service in app_config.get_enabled_extra_services() {
+            for volume_binding in extra_service.volume_bindings.iter().flatten() {
+                // Named volumes would otherwise be resolved against the working directory.
+                if container::is_named_volume(&volume_binding.host_path)
+                    || Path::new(&volume_binding.host_path).exists()
+                {
+                    continue;
+                }
+
+                track_service_entry(
+                    &mut self.service_volume_paths,
+                    &extra_service.name,
+                    &volume_binding.host_path,
+                );
+            }
+        }
+
+        for (service_name, volume_name) in
+            container::get_missing_service_volumes(docker, app_config).await?
+        {
+            track_service_entry(&mut self.service_volumes, &service_name, &volume_name);
+        }
+
+        Ok(())
+    }
+
+    /// Checks if a setup phase completed during a previous, unfinished setup.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - The setup phase.
+    pub fn has_completed_phase(&self, phase: SetupPhase) -> bool {
+        self.completed_setup_phases.contains(&phase)
+    }
+
+    /// Records that a setup phase completed and saves the runtime state right away,
+    /// so the checkpoint survives if setup fails later on.
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - The setup phase.
+    /// * `path` - The path to the runtime state file.
+    pub fn mark_phase_completed(&mut self, phase: SetupPhase, path: &Path) {
+        if !self.has_completed_phase(phase) {
+            self.completed_setup_phases.push(phase);
+        }
+
+        if let Err(err) = self.save(path) {
+            println!("Failed to save the setup checkpoint: {}", err);
+        }
+    }
+
+    /// Saves the runtime state to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the runtime state file.
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| {
+            AppError::GenericError(format!("Failed to serialize runtime state: {}", e))
+        })?;
+
+        fs::write(path, contents).map_err(|e| AppError::IOError(e))
+    }
+}
+
+/// Adds a path or volume to the ones tracked for a service, if it isn't already tracked.
+///
+/// # Arguments
+///
+/// * `tracked` - The tracked paths or volumes, keyed by the service name.
+/// * `service_name` - The name of the service.
+/// * `entry` - The path or volume to track.
+fn track_service_entry(
+    tracked: &mut HashMap<String, Vec<String>>,
+    service_name: &str,
+    entry: &str,
+) {
+    let entries = tracked.entry(service_name.to_string()).or_default();
+
+    if !entries.iter().any(|tracked_entry| tracked_entry == entry) {
+        entries.push(entry.to_string());
+    }
+}
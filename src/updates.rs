@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use bollard::Docker;
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_http::reqwest;
+
+use crate::{
+    config::LlmChatConfig,
+    container, docker,
+    error::AppError,
+    events::{UPDATE_AVAILABLE_EVENT, UpdateAvailable},
+};
+
+/// The GHCR repository for Open WebUI.
+const OPEN_WEBUI_GHCR_REPOSITORY: &'static str = "open-webui/open-webui";
+
+/// The manifest types to accept, so the digest matches what `docker pull` resolves to.
+const MANIFEST_ACCEPT_HEADER: &'static str = "application/vnd.oci.image.index.v1+json, application/vnd.docker.distribution.manifest.list.v2+json, application/vnd.docker.distribution.manifest.v2+json, application/vnd.oci.image.manifest.v1+json";
+
+/// The response from the GHCR token endpoint.
+#[derive(Deserialize, Debug, Clone)]
+struct GhcrTokenResponse {
+    /// The anonymous bearer token for pulling.
+    #[serde(rename = "token")]
+    token: String,
+}
+
+/// Periodically checks for a newer Open WebUI image, emitting `update-available` when one is found.
+///
+/// This runs until the app exits, and does nothing if `update_check_interval_secs` isn't set.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `docker` - The Docker client.
+/// * `app_config` - The application configuration.
+pub async fn run_update_checks(app: AppHandle, docker: Docker, app_config: LlmChatConfig) {
+    let Some(interval_secs) = app_config.update_check_interval_secs else {
+        return;
+    };
+
+    let image = container::get_openwebui_image(&app_config);
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+
+        match check_for_update(&docker, &image, &app_config.openwebui_image_tag).await {
+            Ok(Some(update_available)) => {
+                println!(
+                    "A newer image is available for '{}': {}",
+                    image, update_available.remote_digest
+                );
+
+                let _ = app.emit(UPDATE_AVAILABLE_EVENT, update_available);
+            }
+
+            Ok(None) => {}
+
+            Err(err) => println!("Failed to check for an update to '{}': {}", image, err),
+        }
+    }
+}
+
+/// Checks if the registry has a newer image for the tag than the local one.
+///
+/// # Arguments
+///
+/// * `docker` - The Docker client.
+/// * `image` - The image name and tag.
+/// * `tag` - The tag to check.
+async fn check_for_update(
+    docker: &Docker,
+    image: &str,
+    tag: &str,
+) -> Result<Option<UpdateAvailable>, AppError> {
+    let local_image = docker
+        .inspect_image(image)
+        .await
+        .map_err(|e| docker::map_docker_error(docker, e))?;
+
+    let local_digests: Vec<String> = local_image
+        .repo_digests
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|repo_digest| {
+            repo_digest
+                .split_once('@')
+                .map(|(_, digest)| digest.to_string())
+        })
+        .collect();
+
+    let remote_digest = get_ghcr_digest(OPEN_WEBUI_GHCR_REPOSITORY, tag).await?;
+
+    match local_digests.contains(&remote_digest) {
+        true => Ok(None),
+
+        false => Ok(Some(UpdateAvailable {
+            image: image.to_string(),
+            local_digest: local_digests.into_iter().next(),
+            remote_digest,
+        })),
+    }
+}
+
+/// Gets the current digest for a tag in a public GHCR repository.
+///
+/// # Arguments
+///
+/// * `repository` - The repository (e.g. `open-webui/open-webui`).
+/// * `tag` - The tag to get the digest of.
+async fn get_ghcr_digest(repository: &str, tag: &str) -> Result<String, AppError> {
+    let token_url = format!(
+        "https://ghcr.io/token?scope=repository:{}:pull",
+        repository
+    );
+
+    let token_response = reqwest::get(&token_url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::GenericError(format!("Failed to get a GHCR token: {}", e)))?
+        .json::<GhcrTokenResponse>()
+        .await
+        .map_err(|e| AppError::GenericError(format!("Failed to parse the GHCR token: {}", e)))?;
+
+    let manifest_url = format!("https://ghcr.io/v2/{}/manifests/{}", repository, tag);
+
+    let manifest_response = reqwest::Client::new()
+        .head(&manifest_url)
+        .bearer_auth(&token_response.token)
+        .header("Accept", MANIFEST_ACCEPT_HEADER)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::GenericError(format!("Failed to get the manifest: {}", e)))?;
+
+    manifest_response
+        .headers()
+        .get("Docker-Content-Digest")
+        .and_then(|digest| digest.to_str().ok())
+        .map(|digest| digest.to_string())
+        .ok_or_else(|| {
+            AppError::GenericError("The registry didn't return a digest for the tag".to_string())
+        })
+}
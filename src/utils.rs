@@ -1,10 +1,22 @@
+use rand::Rng;
 use serde::Deserialize;
-use std::{fs, path::PathBuf, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 use tauri::{AppHandle, Manager, Wry};
-use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 use tauri_plugin_http::reqwest;
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::oneshot;
 
-use crate::{config::LlmChatConfig, error::AppError};
+use crate::{
+    config::{ConfigValidationSeverity, LlmChatConfig, Secrets},
+    error::AppError,
+    events::HealthCheckAttempt,
+    progress::{ProgressReporter, TauriProgressReporter},
+};
 
 /// Sets up the local appdata directory for the application.
 ///
@@ -12,50 +24,581 @@ use crate::{config::LlmChatConfig, error::AppError};
 ///
 /// * `app` - The app handle.
 pub fn setup_local_appdata(app: &AppHandle<Wry>) -> Result<(), AppError> {
-    let container_dir = get_app_container_dir(&app)?;
-    ensure_container_data_dir_exists(&container_dir)?;
+    let app_data_dir = get_app_local_data_dir(app)?;
+
+    if !app_data_dir.exists() {
+        fs::create_dir_all(&app_data_dir).map_err(|e| AppError::IOError(e))?;
+    }
+
+    Ok(())
+}
+
+/// The environment variable for overriding the active config profile.
+const PROFILE_ENV_VAR: &'static str = "LOCALLM_PROFILE";
+
+/// The command-line flag for overriding the active config profile.
+const PROFILE_CLI_FLAG: &'static str = "--profile";
+
+/// The command-line flag for removing everything the app created and exiting, without launching the UI.
+const TEARDOWN_CLI_FLAG: &'static str = "--teardown";
+
+/// The command-line flag for printing the resolved config and exiting, without launching the UI.
+const PRINT_CONFIG_CLI_FLAG: &'static str = "--print-config";
+
+/// The environment variable for using a config file outside of the app data directory.
+const CONFIG_PATH_ENV_VAR: &'static str = "LOCALLM_CONFIG_PATH";
+
+/// The environment variable for using a config hosted at a URL, for centrally-managed deployments.
+const CONFIG_URL_ENV_VAR: &'static str = "LOCALLM_CONFIG_URL";
+
+/// The name of the file the config fetched from `LOCALLM_CONFIG_URL` is cached in.
+const REMOTE_CONFIG_CACHE_FILE_NAME: &'static str = "remote_config.yml";
+
+/// The maximum size of a config fetched from `LOCALLM_CONFIG_URL`.
+const REMOTE_CONFIG_MAX_BYTES: usize = 1024 * 1024;
+
+/// How long to wait for the config to be fetched from `LOCALLM_CONFIG_URL`.
+const REMOTE_CONFIG_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The content types a config fetched from `LOCALLM_CONFIG_URL` can have.
+const REMOTE_CONFIG_CONTENT_TYPES: [&'static str; 5] = [
+    "application/yaml",
+    "application/x-yaml",
+    "text/yaml",
+    "text/x-yaml",
+    "text/plain",
+];
+
+/// Gets the local appdata directory for the app.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn get_app_local_data_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path().app_local_data_dir().map_err(|e| {
+        AppError::AppDataDirUnresolvedError(
+            get_app_local_data_dir_source(&app.config().identifier),
+            e.to_string(),
+        )
+    })
+}
+
+/// Resolves the local appdata directory for the app without an app handle.
+///
+/// This is for the command-line flags that exit without launching the UI, and
+/// resolves to the same directory as `get_app_local_data_dir`.
+///
+/// # Arguments
+///
+/// * `identifier` - The app's identifier (e.g. `online.smalls.locallm`).
+pub fn resolve_app_local_data_dir(identifier: &str) -> Result<PathBuf, AppError> {
+    dirs::data_local_dir()
+        .map(|data_local_dir| data_local_dir.join(identifier))
+        .ok_or_else(|| {
+            AppError::AppDataDirUnresolvedError(
+                get_app_local_data_dir_source(identifier),
+                "The local data directory for the current user couldn't be found".to_string(),
+            )
+        })
+}
+
+/// Gets a description of where the local appdata directory is resolved from on this platform.
+///
+/// # Arguments
+///
+/// * `identifier` - The app's identifier.
+fn get_app_local_data_dir_source(identifier: &str) -> String {
+
+    #[cfg(target_os = "macos")]
+    return format!("~/Library/Application Support/{}", identifier);
+
+    #[cfg(target_os = "windows")]
+    return format!("%LOCALAPPDATA%\\{}", identifier);
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    return format!("$XDG_DATA_HOME/{} (or ~/.local/share/{})", identifier, identifier);
+}
+
+/// Gets the path to the config file for the app.
+///
+/// If the `LOCALLM_CONFIG_URL` environment variable is set, the cached copy of the remote
+/// config is used, which must have been fetched with `fetch_remote_config` at least once.
+///
+/// If the `LOCALLM_CONFIG_PATH` environment variable is set, that path is used
+/// instead of `config.yml` in the app data directory. It must already exist and be readable.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn get_app_config_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    get_app_config_path_in(&get_app_local_data_dir(app)?)
+}
+
+/// Gets the path to the config file for the app, relative to the local appdata directory.
+///
+/// See `get_app_config_path` for how the path is chosen.
+///
+/// # Arguments
+///
+/// * `app_data_dir` - The local appdata directory for the app.
+pub fn get_app_config_path_in(app_data_dir: &Path) -> Result<PathBuf, AppError> {
+    if let Some(config_url) = get_remote_config_url() {
+        let cache_path = app_data_dir.join(REMOTE_CONFIG_CACHE_FILE_NAME);
+
+        if !cache_path.is_file() {
+            return Err(AppError::ConfigError(format!(
+                "The config set in {} ({}) hasn't been fetched yet, and there's no cached copy",
+                CONFIG_URL_ENV_VAR, config_url
+            )));
+        }
+
+        return Ok(cache_path);
+    }
+
+    if let Ok(config_path) = std::env::var(CONFIG_PATH_ENV_VAR) {
+        if !config_path.is_empty() {
+            let config_path = PathBuf::from(config_path);
+
+            if !config_path.is_file() {
+                return Err(AppError::ConfigError(format!(
+                    "The config file set in {} doesn't exist or isn't a file: {}",
+                    CONFIG_PATH_ENV_VAR,
+                    config_path.display()
+                )));
+            }
+
+            if let Err(err) = fs::File::open(&config_path) {
+                return Err(AppError::ConfigError(format!(
+                    "The config file set in {} can't be read: {} ({})",
+                    CONFIG_PATH_ENV_VAR,
+                    config_path.display(),
+                    err
+                )));
+            }
+
+            return Ok(config_path);
+        }
+    }
+
+    Ok(app_data_dir.join("config.yml"))
+}
+
+/// Gets the URL set in `LOCALLM_CONFIG_URL`, if any.
+fn get_remote_config_url() -> Option<String> {
+    std::env::var(CONFIG_URL_ENV_VAR)
+        .ok()
+        .filter(|config_url| !config_url.is_empty())
+}
+
+/// Gets the path to the cached copy of the config fetched from `LOCALLM_CONFIG_URL`.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+fn get_remote_config_cache_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = get_app_local_data_dir(app)?;
+
+    Ok(app_data_dir.join(REMOTE_CONFIG_CACHE_FILE_NAME))
+}
+
+/// Fetches the config from `LOCALLM_CONFIG_URL` and caches it, if the environment variable is set.
+///
+/// The config is validated before it replaces the cached copy. If it can't be fetched
+/// (e.g. when offline), the cached copy is used instead, if there is one. A config that's
+/// fetched but is malformed or invalid is always an error.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub async fn fetch_remote_config(app: &AppHandle) -> Result<(), AppError> {
+    let Some(config_url) = get_remote_config_url() else {
+        return Ok(());
+    };
+
+    let cache_path = get_remote_config_cache_path(app)?;
+
+    if let Some(config_contents) = get_remote_config_contents(&config_url, &cache_path).await? {
+        fs::write(&cache_path, &config_contents).map_err(|e| AppError::IOError(e))?;
+    }
 
     Ok(())
 }
 
+/// Fetches and validates the config from `LOCALLM_CONFIG_URL`, without caching it.
+///
+/// Returns `None` if it couldn't be fetched but there's a cached copy to use instead.
+///
+/// # Arguments
+///
+/// * `config_url` - The URL of the config.
+/// * `cache_path` - The path to the cached copy of the config.
+async fn get_remote_config_contents(
+    config_url: &str,
+    cache_path: &Path,
+) -> Result<Option<String>, AppError> {
+    println!("Fetching the config from '{}'", config_url);
+
+    let config_contents = match download_remote_config(config_url).await {
+        Ok(config_contents) => config_contents,
+
+        Err(err) if cache_path.is_file() => {
+            println!(
+                "Warning: Failed to fetch the config, using the cached copy instead: {}",
+                err
+            );
+
+            return Ok(None);
+        }
+
+        Err(err) => return Err(err),
+    };
+
+    let remote_config =
+        LlmChatConfig::from_yaml_with_profile(&config_contents, get_profile_override()).map_err(
+            |e| AppError::ConfigError(format!("The config from '{}' is malformed: {}", config_url, e)),
+        )?;
+
+    let validation_errors: Vec<_> = remote_config
+        .validate()
+        .into_iter()
+        .filter(|validation_error| validation_error.severity == ConfigValidationSeverity::Error)
+        .collect();
+
+    if !validation_errors.is_empty() {
+        return Err(AppError::InvalidConfigError(validation_errors));
+    }
+
+    Ok(Some(config_contents))
+}
+
+/// Downloads the config from a URL, checking its content type and size.
+///
+/// # Arguments
+///
+/// * `config_url` - The URL of the config.
+async fn download_remote_config(config_url: &str) -> Result<String, AppError> {
+    let response = reqwest::Client::new()
+        .get(config_url)
+        .timeout(REMOTE_CONFIG_TIMEOUT)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| AppError::ConfigError(format!("Failed to fetch the config: {}", e)))?;
+
+    let content_type = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|content_type| content_type.trim().to_ascii_lowercase());
+
+    if let Some(content_type) = content_type {
+        if !REMOTE_CONFIG_CONTENT_TYPES.contains(&content_type.as_str()) {
+            return Err(AppError::ConfigError(format!(
+                "The config from '{}' has the content type '{}', but it must be YAML",
+                config_url, content_type
+            )));
+        }
+    }
+
+    if response
+        .content_length()
+        .is_some_and(|content_length| content_length > REMOTE_CONFIG_MAX_BYTES as u64)
+    {
+        return Err(AppError::ConfigError(format!(
+            "The config from '{}' is larger than {} bytes",
+            config_url, REMOTE_CONFIG_MAX_BYTES
+        )));
+    }
+
+    let config_bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::ConfigError(format!("Failed to fetch the config: {}", e)))?;
+
+    // The content length isn't always sent, so the body is checked too.
+    if config_bytes.len() > REMOTE_CONFIG_MAX_BYTES {
+        return Err(AppError::ConfigError(format!(
+            "The config from '{}' is larger than {} bytes",
+            config_url, REMOTE_CONFIG_MAX_BYTES
+        )));
+    }
+
+    String::from_utf8(config_bytes.to_vec()).map_err(|_| {
+        AppError::ConfigError(format!("The config from '{}' isn't valid UTF-8", config_url))
+    })
+}
+
+/// Gets the path to the file the runtime state is persisted to.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+pub fn get_runtime_state_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let app_data_dir = get_app_local_data_dir(app)?;
+
+    Ok(app_data_dir.join("runtime_state.json"))
+}
+
 /// Get the config for the app.
+///
+/// If a profile is active, it's merged over the base config.
+/// If the config file doesn't exist yet, it's created with the default config.
 /// 
 /// # Arguments
 /// 
 /// * `app` - The app handle.
 pub fn get_app_config(app: &AppHandle) -> Result<LlmChatConfig, AppError> {
-    let app_data_dir = app
-        .path()
-        .app_local_data_dir()
-        .map_err(|e| AppError::TauriError(e))?;
+    let config_file_path = get_app_config_path(app)?;
+
+    if !config_file_path.exists() {
+        let default_config_contents = get_default_config_contents()?;
+
+        std::fs::write(&config_file_path, &default_config_contents)
+            .map_err(|_| AppError::GenericError("Failed to write default config file".to_string()))?;
+    }
 
-    let config_file_path = app_data_dir.join("config.yml");
+    read_app_config(&config_file_path)
+}
+
+/// Gets the default config as YAML.
+fn get_default_config_contents() -> Result<String, AppError> {
+    serde_yaml::to_string::<LlmChatConfig>(&LlmChatConfig::default())
+        .map_err(|e| AppError::YamlError(e))
+}
 
-    let app_config = match config_file_path.exists() {
+/// Reads and validates the config for the app from a file, without writing anything.
+///
+/// If a profile is active, it's merged over the base config. If the file doesn't exist,
+/// the default config is used.
+///
+/// # Arguments
+///
+/// * `config_file_path` - The path to the config file.
+pub fn read_app_config(config_file_path: &Path) -> Result<LlmChatConfig, AppError> {
+    let config_file_contents = match config_file_path.exists() {
         true => {
             let config_file_contents =
-                std::fs::read(&config_file_path).map_err(|e| AppError::IOError(e))?;
-            let config_file_contents = String::from_utf8(config_file_contents)
-                .map_err(|_| AppError::GenericError("Failed to read config file".to_string()))?;
+                std::fs::read(config_file_path).map_err(|e| AppError::IOError(e))?;
 
-            serde_yaml::from_str::<LlmChatConfig>(&config_file_contents).map_err(|e| AppError::YamlError(e))?
+            String::from_utf8(config_file_contents)
+                .map_err(|_| AppError::GenericError("Failed to read config file".to_string()))?
         }
 
-        false => {
-            let default_config = LlmChatConfig::default();
-            let default_config_contents = serde_yaml::to_string::<LlmChatConfig>(&default_config)
-                .map_err(|e| AppError::YamlError(e))?;
+        false => get_default_config_contents()?,
+    };
+
+    parse_app_config(&config_file_contents)
+}
 
-            std::fs::write(&config_file_path, &default_config_contents)
-                .map_err(|_| AppError::GenericError("Failed to write default config file".to_string()))?;
+/// Loads and validates the config for the app without an app handle or writing anything.
+///
+/// This is for the command-line flags that exit without launching the UI. If the
+/// `LOCALLM_CONFIG_URL` environment variable is set, the config is fetched from it,
+/// falling back to the cached copy, but the cached copy isn't updated.
+///
+/// # Arguments
+///
+/// * `app_data_dir` - The local appdata directory for the app.
+pub async fn load_app_config_read_only(app_data_dir: &Path) -> Result<LlmChatConfig, AppError> {
+    if let Some(config_url) = get_remote_config_url() {
+        let cache_path = app_data_dir.join(REMOTE_CONFIG_CACHE_FILE_NAME);
 
-            default_config
+        if let Some(config_contents) = get_remote_config_contents(&config_url, &cache_path).await? {
+            return parse_app_config(&config_contents);
         }
-    };
+    }
+
+    read_app_config(&get_app_config_path_in(app_data_dir)?)
+}
+
+/// Parses and validates the config for the app, merging the active profile over it.
+///
+/// Validation warnings are logged, and validation errors are returned as an error.
+///
+/// # Arguments
+///
+/// * `config_file_contents` - The contents of the config file.
+fn parse_app_config(config_file_contents: &str) -> Result<LlmChatConfig, AppError> {
+    let app_config =
+        LlmChatConfig::from_yaml_with_profile(config_file_contents, get_profile_override())?;
+
+    let (validation_errors, validation_warnings): (Vec<_>, Vec<_>) = app_config
+        .validate()
+        .into_iter()
+        .partition(|validation_error| validation_error.severity == ConfigValidationSeverity::Error);
+
+    for validation_warning in &validation_warnings {
+        println!("Config warning: {}", validation_warning);
+    }
+
+    if !validation_errors.is_empty() {
+        return Err(AppError::InvalidConfigError(validation_errors));
+    }
 
     Ok(app_config)
 }
 
+/// Gets the profile to use instead of the `active_profile` set in the config, if any.
+///
+/// The `LOCALLM_PROFILE` environment variable takes precedence over the `--profile` command-line flag.
+pub fn get_profile_override() -> Option<String> {
+    if let Ok(profile) = std::env::var(PROFILE_ENV_VAR) {
+        if !profile.is_empty() {
+            return Some(profile);
+        }
+    }
+
+    let mut args = std::env::args();
+
+    while let Some(arg) = args.next() {
+        if arg == PROFILE_CLI_FLAG {
+            return args.next();
+        }
+
+        if let Some(profile) = arg.strip_prefix(&format!("{}=", PROFILE_CLI_FLAG)) {
+            return Some(profile.to_string());
+        }
+    }
+
+    None
+}
+
+/// Checks whether a port on the host is free to bind to.
+///
+/// # Arguments
+///
+/// * `port` - The port to check.
+pub fn is_host_port_available(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// Checks whether the app was launched with the `--teardown` flag.
+pub fn is_teardown_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == TEARDOWN_CLI_FLAG)
+}
+
+/// Checks whether the app was launched with the `--print-config` flag.
+pub fn is_print_config_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == PRINT_CONFIG_CLI_FLAG)
+}
+
+/// Sets the `active_profile` in the config file.
+///
+/// The config file is rewritten, so any YAML anchors in it are written out in full
+/// (merges still behave the same) and comments are lost.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `profile` - The name of the profile to make active.
+pub fn set_active_profile(app: &AppHandle, profile: &str) -> Result<(), AppError> {
+    update_config_file(app, |config_mapping| {
+        let profile_exists = config_mapping
+            .get("profiles")
+            .and_then(|profiles| profiles.get(profile))
+            .is_some();
+
+        if !profile_exists {
+            return Err(AppError::ConfigError(format!(
+                "Profile '{}' doesn't exist",
+                profile
+            )));
+        }
+
+        config_mapping.insert(
+            serde_yaml::Value::String("active_profile".to_string()),
+            serde_yaml::Value::String(profile.to_string()),
+        );
+
+        Ok(())
+    })
+}
+
+/// Sets the `openwebui_image_tag` in the config file.
+///
+/// If the active profile sets the tag, it's changed in the profile, so the
+/// new tag isn't overridden by it. Otherwise it's changed in the base config.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `active_profile` - The name of the active profile, if any.
+/// * `tag` - The image tag to set.
+pub fn set_openwebui_image_tag(
+    app: &AppHandle,
+    active_profile: Option<&str>,
+    tag: &str,
+) -> Result<(), AppError> {
+    update_config_file(app, |config_mapping| {
+        let tag_key = serde_yaml::Value::String("openwebui_image_tag".to_string());
+        let tag_value = serde_yaml::Value::String(tag.to_string());
+
+        let profile_mapping = active_profile.and_then(|active_profile| {
+            config_mapping
+                .get_mut("profiles")
+                .and_then(|profiles| profiles.get_mut(active_profile))
+                .and_then(|profile| profile.as_mapping_mut())
+                .filter(|profile_mapping| profile_mapping.contains_key(&tag_key))
+        });
+
+        match profile_mapping {
+            Some(profile_mapping) => profile_mapping.insert(tag_key, tag_value),
+            None => config_mapping.insert(tag_key, tag_value),
+        };
+
+        Ok(())
+    })
+}
+
+/// Reads the config file, updates it, and writes it back.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `update` - Updates the top-level mapping of the config file.
+fn update_config_file(
+    app: &AppHandle,
+    update: impl FnOnce(&mut serde_yaml::Mapping) -> Result<(), AppError>,
+) -> Result<(), AppError> {
+    let config_file_path = get_app_config_path(app)?;
+
+    let config_file_contents =
+        std::fs::read_to_string(&config_file_path).map_err(|e| AppError::IOError(e))?;
+
+    let mut config_value = serde_yaml::from_str::<serde_yaml::Value>(&config_file_contents)
+        .map_err(|e| AppError::YamlError(e))?;
+
+    let Some(config_mapping) = config_value.as_mapping_mut() else {
+        return Err(AppError::GenericError(
+            "The config file isn't a YAML mapping".to_string(),
+        ));
+    };
+
+    update(config_mapping)?;
+
+    let config_file_contents =
+        serde_yaml::to_string(&config_value).map_err(|e| AppError::YamlError(e))?;
+
+    std::fs::write(&config_file_path, config_file_contents).map_err(|e| AppError::IOError(e))?;
+
+    Ok(())
+}
+
+/// Shows an error dialog for a setup failure.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `error` - The error to show.
+pub fn show_setup_error(app: &AppHandle<Wry>, error: &AppError) {
+    app.dialog()
+        .message(format!("Setup failed: {}", error.to_string()))
+        .kind(MessageDialogKind::Error)
+        .title("Error")
+        .blocking_show();
+}
+
 /// Shows an error dialog for local appdata setup failure.
 ///
 /// # Arguments
@@ -63,16 +606,117 @@ pub fn get_app_config(app: &AppHandle) -> Result<LlmChatConfig, AppError> {
 /// * `app` - The app handle.
 /// * `error` - The error to show.
 pub fn show_setup_local_appdata_error(app: &AppHandle<Wry>, error: &AppError) {
+    let message = match error {
+        AppError::AppDataDirUnresolvedError(source, detail) => format!(
+            "The app couldn't determine where to store its data.\n\nCheck that your user account has permission to access its local app data folder, and that any roaming profile settings aren't blocking it.\n\nAttempted location: {}\nDetails: {}",
+            source, detail
+        ),
+
+        _ => format!("Failed to setup local app data: {}", error.to_string()),
+    };
+
     app.dialog()
-        .message(format!(
-            "Failed to setup local app data: {}",
-            error.to_string()
-        ))
+        .message(message)
         .kind(MessageDialogKind::Error)
         .title("Error")
         .blocking_show();
 }
 
+/// Shows a dialog offering to open Open WebUI in the system browser,
+/// when it didn't load in the main window.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `webui_url` - The URL for Open WebUI.
+pub fn show_webui_load_timeout(app: &AppHandle<Wry>, webui_url: &str) {
+    let opener_app = app.clone();
+    let webui_url = webui_url.to_string();
+
+    app.dialog()
+        .message(format!(
+            "Open WebUI is running, but it hasn't loaded in the app window.\n\nA VPN or firewall may be blocking the app from reaching localhost. You can open it in your browser instead: {}",
+            webui_url
+        ))
+        .kind(MessageDialogKind::Warning)
+        .title("Open WebUI isn't loading")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Open in Browser".to_string(),
+            "Keep Waiting".to_string(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+
+            if let Err(err) = opener_app.opener().open_url(&webui_url, None::<&str>) {
+                println!("Failed to open '{}' in the browser: {}", webui_url, err);
+            }
+        });
+}
+
+/// Asks the user to confirm deleting the host paths and named volumes that were created
+/// for a service.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `service_name` - The name of the service.
+/// * `volume_paths` - The host paths that would be deleted.
+/// * `volumes` - The named volumes that would be deleted.
+pub async fn confirm_purge_service_data(
+    app: &AppHandle<Wry>,
+    service_name: &str,
+    volume_paths: &[String],
+    volumes: &[String],
+) -> bool {
+    let (sender, receiver) = oneshot::channel();
+
+    let data = volume_paths
+        .iter()
+        .cloned()
+        .chain(volumes.iter().map(|volume| format!("Docker volume '{}'", volume)))
+        .collect::<Vec<String>>();
+
+    app.dialog()
+        .message(format!(
+            "Delete the data created for '{}'? This can't be undone.\n\n{}",
+            service_name,
+            data.join("\n")
+        ))
+        .kind(MessageDialogKind::Warning)
+        .title("Purge service")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Delete".to_string(),
+            "Keep".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = sender.send(confirmed);
+        });
+
+    receiver.await.unwrap_or(false)
+}
+
+/// Removes a file or directory, returning whether it existed.
+///
+/// # Arguments
+///
+/// * `path` - The path to remove.
+pub fn remove_path(path: &Path) -> Result<bool, AppError> {
+    let remove_result = match path.is_dir() {
+        true => fs::remove_dir_all(path),
+        false => fs::remove_file(path),
+    };
+
+    match remove_result {
+        Ok(_) => Ok(true),
+
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+
+        Err(err) => Err(AppError::IOError(err)),
+    }
+}
+
 /// Shows an error dialog for a Docker failure.
 ///
 /// # Arguments
@@ -80,8 +724,11 @@ pub fn show_setup_local_appdata_error(app: &AppHandle<Wry>, error: &AppError) {
 /// * `app` - The app handle.
 /// * `error` - The error to show.
 pub fn show_docker_error(app: &AppHandle<Wry>, error: &AppError) {
+    // API version mismatches are mapped to `DockerApiVersionMismatch`, which includes the advice.
+    let message = format!("Docker failure: {}", error.to_string());
+
     app.dialog()
-        .message(format!("Docker failure: {}", error.to_string()))
+        .message(message)
         .kind(MessageDialogKind::Error)
         .title("Error")
         .blocking_show();
@@ -94,13 +741,14 @@ pub fn show_docker_error(app: &AppHandle<Wry>, error: &AppError) {
 /// # Arguments
 ///
 /// * `app` - The app handle.
-pub fn get_app_container_dir(app: &AppHandle<Wry>) -> Result<PathBuf, AppError> {
-    let app_data_dir = app
-        .path()
-        .app_local_data_dir()
-        .map_err(|e| AppError::TauriError(e))?;
+/// * `app_config` - The application configuration.
+pub fn get_app_container_dir(
+    app: &AppHandle<Wry>,
+    app_config: &LlmChatConfig,
+) -> Result<PathBuf, AppError> {
+    let app_data_dir = get_app_local_data_dir(app)?;
 
-    let app_container_dir = app_data_dir.join("openwebui");
+    let app_container_dir = app_data_dir.join(&app_config.container_dir_name);
 
     if !app_container_dir.exists() {
         fs::create_dir_all(&app_container_dir).map_err(|e| AppError::IOError(e))?;
@@ -109,23 +757,316 @@ pub fn get_app_container_dir(app: &AppHandle<Wry>) -> Result<PathBuf, AppError>
     Ok(app_container_dir)
 }
 
-/// Ensures that the `data` directory exists in the container directory.
+/// Ensures that the data directory exists in the container directory.
 ///
-/// If the `data` directory does not exist, it will create it.
+/// If the data directory does not exist, it will create it.
 ///
 /// # Arguments
 ///
 /// * `container_dir` - The path to the container directory.
-fn ensure_container_data_dir_exists(container_dir: &PathBuf) -> Result<(), AppError> {
-    let data_dir = container_dir.join("data");
+/// * `data_dir_name` - The name of the data directory.
+fn ensure_container_data_dir_exists(
+    container_dir: &PathBuf,
+    data_dir_name: &str,
+) -> Result<PathBuf, AppError> {
+    let data_dir = container_dir.join(data_dir_name);
 
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir).map_err(|e| AppError::IOError(e))?;
     }
 
+    Ok(data_dir)
+}
+
+/// Gets the path to the Open WebUI data directory, creating it if it doesn't exist.
+///
+/// This is `data_dir` when it's set in the config, otherwise it's in the app data directory.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `app_config` - The application configuration.
+pub fn get_app_data_dir(app: &AppHandle<Wry>, app_config: &LlmChatConfig) -> Result<PathBuf, AppError> {
+    if let Some(data_dir) = &app_config.data_dir {
+        let data_dir = PathBuf::from(data_dir);
+
+        if !data_dir.exists() {
+            fs::create_dir_all(&data_dir).map_err(|e| AppError::IOError(e))?;
+        }
+
+        return Ok(data_dir);
+    }
+
+    let container_dir = get_app_container_dir(app, app_config)?;
+
+    ensure_container_data_dir_exists(&container_dir, &app_config.data_dir_name)
+}
+
+/// Gets the data directory left in the app data directory that should be migrated to `data_dir`, if any.
+///
+/// There's only something to migrate if `data_dir` is set, the old data directory has
+/// files in it, and `data_dir` doesn't have any files yet.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `app_config` - The application configuration.
+pub fn get_data_dir_to_migrate(
+    app: &AppHandle<Wry>,
+    app_config: &LlmChatConfig,
+) -> Result<Option<(PathBuf, PathBuf)>, AppError> {
+    let Some(data_dir) = &app_config.data_dir else {
+        return Ok(None);
+    };
+
+    let old_data_dir = get_app_local_data_dir(app)?
+        .join(&app_config.container_dir_name)
+        .join(&app_config.data_dir_name);
+    let new_data_dir = PathBuf::from(data_dir);
+
+    if old_data_dir == new_data_dir || is_dir_empty(&old_data_dir) || !is_dir_empty(&new_data_dir) {
+        return Ok(None);
+    }
+
+    Ok(Some((old_data_dir, new_data_dir)))
+}
+
+/// Checks whether a directory is empty, treating a missing directory as empty.
+///
+/// # Arguments
+///
+/// * `dir` - The path to the directory.
+fn is_dir_empty(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true)
+}
+
+/// Asks the user whether to migrate the data directory to the new location.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `old_data_dir` - The path to the old data directory.
+/// * `new_data_dir` - The path to the new data directory.
+pub fn confirm_data_dir_migration(app: &AppHandle<Wry>, old_data_dir: &Path, new_data_dir: &Path) -> bool {
+    app.dialog()
+        .message(format!(
+            "Open WebUI's data was found in the old location:\n{}\n\nDo you want to move it to the location set in the config?\n{}\n\nIf you skip this, Open WebUI starts with no data. You'll be asked again on the next launch.",
+            old_data_dir.display(),
+            new_data_dir.display()
+        ))
+        .kind(MessageDialogKind::Info)
+        .title("Migrate data")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Migrate".to_string(),
+            "Skip".to_string(),
+        ))
+        .blocking_show()
+}
+
+/// Moves a data directory to a new location.
+///
+/// The data is copied to a staging directory next to the new location first, and the
+/// copy is checked against the original before being renamed into place, so the
+/// original is never touched if anything goes wrong. Once the copy is in place, the
+/// original is renamed with a `.migrated` extension rather than deleted, in case
+/// it's still needed.
+///
+/// # Arguments
+///
+/// * `old_data_dir` - The path to the data directory to migrate.
+/// * `new_data_dir` - The path to migrate the data directory to. It must be empty or not exist.
+pub fn migrate_data_dir(old_data_dir: &Path, new_data_dir: &Path) -> Result<(), AppError> {
+    if !is_dir_empty(new_data_dir) {
+        return Err(AppError::GenericError(format!(
+            "Can't migrate the data directory to '{}', since it isn't empty",
+            new_data_dir.display()
+        )));
+    }
+
+    let staging_dir = new_data_dir.with_extension("migrating");
+
+    // Clean up anything left behind by an earlier migration that was interrupted.
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir).map_err(|e| AppError::IOError(e))?;
+    }
+
+    if let Some(parent_dir) = staging_dir.parent() {
+        fs::create_dir_all(parent_dir).map_err(|e| AppError::IOError(e))?;
+    }
+
+    let copy_result = copy_dir_all(old_data_dir, &staging_dir).and_then(|_| {
+        match list_dir_files(old_data_dir)? == list_dir_files(&staging_dir)? {
+            true => Ok(()),
+            false => Err(std::io::Error::other(
+                "The copied files don't match the original files",
+            )),
+        }
+    });
+
+    if let Err(err) = copy_result {
+        let _ = fs::remove_dir_all(&staging_dir);
+
+        return Err(AppError::GenericError(format!(
+            "Failed to copy the data directory to '{}': {}",
+            new_data_dir.display(),
+            err
+        )));
+    }
+
+    // An empty directory at the new location (e.g. created by an earlier launch) is replaced.
+    if new_data_dir.exists() {
+        fs::remove_dir(new_data_dir).map_err(|e| AppError::IOError(e))?;
+    }
+
+    fs::rename(&staging_dir, new_data_dir).map_err(|e| AppError::IOError(e))?;
+
+    let migrated_dir = old_data_dir.with_extension("migrated");
+
+    if let Err(err) = fs::rename(old_data_dir, &migrated_dir) {
+        println!(
+            "Failed to rename the old data directory '{}': {}",
+            old_data_dir.display(),
+            err
+        );
+    }
+
+    println!(
+        "Migrated the data directory from '{}' to '{}'",
+        old_data_dir.display(),
+        new_data_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Copies a directory and its contents, recreating symlinks rather than following them.
+///
+/// # Arguments
+///
+/// * `source_dir` - The directory to copy.
+/// * `target_dir` - The path to copy the directory to.
+fn copy_dir_all(source_dir: &Path, target_dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target_dir)?;
+
+    for entry in fs::read_dir(source_dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target_path = target_dir.join(entry.file_name());
+
+        if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target_path)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target_path)?;
+        } else {
+            fs::copy(entry.path(), &target_path)?;
+        }
+    }
+
     Ok(())
 }
 
+/// Recreates a symlink at a new path, pointing at the same target.
+///
+/// # Arguments
+///
+/// * `source_path` - The symlink to copy.
+/// * `target_path` - The path to create the symlink at.
+fn copy_symlink(source_path: &Path, target_path: &Path) -> std::io::Result<()> {
+    let link_target = fs::read_link(source_path)?;
+
+    #[cfg(unix)]
+    return std::os::unix::fs::symlink(link_target, target_path);
+
+    #[cfg(windows)]
+    return match source_path.metadata().is_ok_and(|metadata| metadata.is_dir()) {
+        true => std::os::windows::fs::symlink_dir(link_target, target_path),
+        false => std::os::windows::fs::symlink_file(link_target, target_path),
+    };
+}
+
+/// Lists the files in a directory and its subdirectories, with the size of each.
+///
+/// The paths are relative to the directory and sorted, so two listings can be compared.
+///
+/// # Arguments
+///
+/// * `dir` - The directory to list.
+fn list_dir_files(dir: &Path) -> std::io::Result<Vec<(PathBuf, u64)>> {
+    let mut files = Vec::new();
+    let mut pending_dirs = vec![dir.to_path_buf()];
+
+    while let Some(current_dir) = pending_dirs.pop() {
+        for entry in fs::read_dir(&current_dir)? {
+            let entry = entry?;
+            let metadata = fs::symlink_metadata(entry.path())?;
+            let relative_path = entry
+                .path()
+                .strip_prefix(dir)
+                .map(|path| path.to_path_buf())
+                .map_err(std::io::Error::other)?;
+
+            match metadata.is_dir() {
+                true => {
+                    pending_dirs.push(entry.path());
+                    files.push((relative_path, 0));
+                }
+
+                false => files.push((relative_path, metadata.len())),
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Loads the secrets from a dotenv-style file.
+///
+/// # Arguments
+///
+/// * `path` - The path to the secrets file.
+pub fn load_secrets_file(path: &Path) -> Result<Secrets, AppError> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        AppError::ConfigError(format!(
+            "Failed to read the secrets file '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    Secrets::parse(&contents)
+}
+
+/// Gets the size of a file, or the total size of a directory's contents, in bytes.
+///
+/// Symlinks aren't followed, and anything that can't be read (e.g. files owned by
+/// a container's user) is skipped rather than failing the whole walk.
+///
+/// # Arguments
+///
+/// * `path` - The path to get the size of.
+pub fn get_path_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| get_path_size(&entry.path()))
+        .sum()
+}
+
 /// Represents the status of Open WebUI retrieved from the `/health` endpoint.
 #[derive(Deserialize, Debug, Clone)]
 pub struct OpenWebUiHealthStatus {
@@ -134,31 +1075,279 @@ pub struct OpenWebUiHealthStatus {
     pub status: bool,
 }
 
-/// Wait until the Open WebUI server is healthy.
+/// The endpoints to check Open WebUI's health on, in the order they're tried.
+///
+/// Older Open WebUI versions don't have `/health`, so the others are fallbacks.
+const HEALTH_ENDPOINTS: [&str; 3] = ["/health", "/api/health", "/"];
+
+/// The timeout for a single readiness check.
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The timeout for a single health check request while waiting for Open WebUI.
+const HEALTH_CHECK_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum amount of time to wait for Open WebUI to become healthy.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The interval, in milliseconds, between the first health check attempts.
+const HEALTH_CHECK_BASE_INTERVAL_MS: u64 = 500;
+
+/// The longest interval, in milliseconds, between health check attempts.
+const HEALTH_CHECK_MAX_INTERVAL_MS: u64 = 5000;
+
+/// The maximum amount of random jitter, in milliseconds, added to each health check interval.
+const HEALTH_CHECK_MAX_JITTER_MS: u64 = 250;
+
+/// Runs the post-setup hook on the host, logging its output.
+///
+/// Failures are only logged, since the app is already usable by the time this runs.
 ///
 /// # Arguments
 ///
-/// * `app` - The app handle.
-pub async fn wait_until_openwebui_is_healthy(app: &AppHandle<Wry>) -> Result<(), AppError> {
-    // I can almost guarantee that this can be done muuuuuuch better.
-    // But hey! That's thrown together code for ya. :P
-    let mut counter = 0;
-    while counter < 120 {
-        let api_response_result = reqwest::get("http://localhost:11690/health").await;
+/// * `hook` - The command and args to run.
+/// * `webui_url` - The URL for Open WebUI.
+/// * `host_port` - The port on the host that Open WebUI is bound to.
+pub async fn run_post_setup_hook(hook: Vec<String>, webui_url: String, host_port: u16) {
+    let Some((program, args)) = hook.split_first() else {
+        return;
+    };
 
-        if let Ok(response) = api_response_result {
-            let status_result = response.json::<OpenWebUiHealthStatus>().await;
+    println!("Running post-setup hook: {}", hook.join(" "));
 
-            if let Ok(status_data) = status_result {
-                if status_data.status {
-                    return Ok(());
-                }
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .env("LOCALLM_WEBUI_URL", &webui_url)
+        .env("LOCALLM_WEBUI_PORT", host_port.to_string())
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+
+            if !stdout.is_empty() {
+                println!("Post-setup hook stdout:\n{}", stdout);
+            }
+
+            if !stderr.is_empty() {
+                println!("Post-setup hook stderr:\n{}", stderr);
+            }
+
+            if !output.status.success() {
+                println!("Warning: The post-setup hook exited with {}", output.status);
             }
         }
 
-        counter += 1;
+        Err(err) => println!("Warning: Failed to run the post-setup hook: {}", err),
+    }
+}
+
+/// Gets the URL of an Open WebUI health endpoint.
+///
+/// # Arguments
+///
+/// * `webui_url` - The URL for Open WebUI.
+/// * `endpoint` - The path of the endpoint (e.g. `/health`).
+fn get_health_url(webui_url: &str, endpoint: &str) -> String {
+    format!("{}{}", webui_url.trim_end_matches('/'), endpoint)
+}
+
+/// Checks whether an external Ollama server can be reached from the host.
+///
+/// `host.docker.internal` only resolves in containers, so it's checked as `localhost`.
+///
+/// # Arguments
+///
+/// * `external_ollama_url` - The URL of the external Ollama server.
+pub async fn is_external_ollama_reachable(external_ollama_url: &str) -> bool {
+    let Ok(mut url) = reqwest::Url::parse(external_ollama_url) else {
+        return false;
+    };
+
+    if url.host_str() == Some("host.docker.internal") && url.set_host(Some("localhost")).is_err() {
+        return false;
+    }
+
+    is_port_reachable(url.as_str()).await
+}
+
+/// Checks whether a TCP connection can be made to the host and port of a URL.
+///
+/// # Arguments
+///
+/// * `webui_url` - The URL for Open WebUI.
+async fn is_port_reachable(webui_url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(webui_url) else {
+        return false;
+    };
+
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return false;
+    };
+
+    matches!(
+        tokio::time::timeout(
+            READINESS_CHECK_TIMEOUT,
+            tokio::net::TcpStream::connect((host, port))
+        )
+        .await,
+        Ok(Ok(_))
+    )
+}
+
+/// Checks if Open WebUI is healthy, trying each of the `HEALTH_ENDPOINTS` in order.
+///
+/// The first endpoint with a 2xx response decides the result: it's healthy unless the
+/// response has a health status that says otherwise. Otherwise, the result describes
+/// the last endpoint that was checked.
+///
+/// # Arguments
+///
+/// * `client` - The HTTP client.
+/// * `webui_url` - The URL for Open WebUI.
+/// * `attempt` - The number of attempts made so far.
+async fn check_health(client: &reqwest::Client, webui_url: &str, attempt: u64) -> HealthCheckAttempt {
+    let mut health_check = HealthCheckAttempt {
+        attempt,
+        tcp_connected: is_port_reachable(webui_url).await,
+        ..Default::default()
+    };
+
+    if !health_check.tcp_connected {
+        return health_check;
+    }
+
+    for endpoint in HEALTH_ENDPOINTS {
+        health_check = HealthCheckAttempt {
+            attempt,
+            endpoint: Some(endpoint.to_string()),
+            tcp_connected: true,
+            ..Default::default()
+        };
+
+        let response = match client.get(get_health_url(webui_url, endpoint)).send().await {
+            Ok(response) => response,
+
+            // The other endpoints won't respond either if the server isn't up.
+            Err(_) => return health_check,
+        };
+
+        health_check.http_responded = true;
+        health_check.status_code = Some(response.status().as_u16());
+
+        if !response.status().is_success() {
+            continue;
+        }
+
+        match response.json::<OpenWebUiHealthStatus>().await {
+            Ok(status_data) => {
+                health_check.json_parsed = true;
+                health_check.healthy = status_data.status;
+            }
+
+            // Endpoints like `/` don't return a health status, so the 2xx is enough.
+            Err(_) => health_check.healthy = true,
+        }
 
-        std::thread::sleep(Duration::from_secs(1));
+        // A reported status of unhealthy shouldn't be overridden by a later fallback.
+        return health_check;
+    }
+
+    health_check
+}
+
+/// Checks once if Open WebUI is healthy, without retrying.
+///
+/// # Arguments
+///
+/// * `webui_url` - The URL for Open WebUI.
+pub async fn is_openwebui_ready(webui_url: &str) -> Result<bool, AppError> {
+    let client = reqwest::Client::builder()
+        .timeout(READINESS_CHECK_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::GenericError(format!("Failed to create the HTTP client: {}", e)))?;
+
+    // Not being able to connect just means it isn't ready yet.
+    Ok(check_health(&client, webui_url, 0).await.healthy)
+}
+
+/// Gets how long to wait before the next health check attempt.
+///
+/// The interval grows with each attempt, up to a maximum, and has a small
+/// amount of random jitter added, so a slow system isn't polled in a tight loop
+/// while Open WebUI is still starting up.
+///
+/// # Arguments
+///
+/// * `attempt` - The number of attempts made so far.
+fn get_health_check_interval(attempt: u64) -> Duration {
+    let interval_ms = HEALTH_CHECK_BASE_INTERVAL_MS
+        .saturating_mul(attempt + 1)
+        .min(HEALTH_CHECK_MAX_INTERVAL_MS);
+
+    let jitter_ms = rand::rng().random_range(0..=HEALTH_CHECK_MAX_JITTER_MS);
+
+    Duration::from_millis(interval_ms + jitter_ms)
+}
+
+/// Waits for the Open WebUI server to become healthy, without showing an error if it doesn't.
+///
+/// Returns `false` if it isn't healthy before the health check timeout.
+///
+/// # Arguments
+///
+/// * `reporter` - Receives the result of each attempt.
+/// * `webui_url` - The base URL for Open WebUI.
+pub async fn wait_for_openwebui_health(reporter: &dyn ProgressReporter, webui_url: &str) -> bool {
+    let client = match reqwest::Client::builder().timeout(HEALTH_CHECK_REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            println!("Failed to create the HTTP client: {}", e);
+
+            return false;
+        }
+    };
+    let started_at = Instant::now();
+
+    let mut attempt = 0;
+    while started_at.elapsed() < HEALTH_CHECK_TIMEOUT {
+        let health_check = check_health(&client, webui_url, attempt).await;
+        let healthy_endpoint = health_check.endpoint.clone().filter(|_| health_check.healthy);
+
+        reporter.health_check(health_check);
+
+        if let Some(endpoint) = healthy_endpoint {
+            println!("Open WebUI is healthy (checked '{}')", endpoint);
+
+            return true;
+        }
+
+        tokio::time::sleep(get_health_check_interval(attempt)).await;
+
+        attempt += 1;
+    }
+
+    false
+}
+
+/// Wait until the Open WebUI server is healthy.
+///
+/// Each of the `HEALTH_ENDPOINTS` is tried in order, so older Open WebUI versions
+/// without `/health` are still detected.
+///
+/// # Arguments
+///
+/// * `app` - The app handle.
+/// * `webui_url` - The base URL for Open WebUI.
+pub async fn wait_until_openwebui_is_healthy(
+    app: &AppHandle<Wry>,
+    webui_url: &str,
+) -> Result<(), AppError> {
+    let reporter = TauriProgressReporter::new(app.clone());
+
+    if wait_for_openwebui_health(&reporter, webui_url).await {
+        return Ok(());
     }
 
     app.dialog()
@@ -169,3 +1358,87 @@ pub async fn wait_until_openwebui_is_healthy(app: &AppHandle<Wry>) -> Result<(),
 
     Err(AppError::GenericError("Startup took too long".to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Starts an HTTP server that responds to each path with the given status and body.
+    ///
+    /// # Arguments
+    ///
+    /// * `responses` - The path, status line and body for each response.
+    async fn start_test_server(
+        responses: Vec<(&'static str, &'static str, &'static str)>,
+    ) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let webui_url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = vec![0u8; 4096];
+                let read = stream.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]).to_string();
+
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let (status, body) = responses
+                    .iter()
+                    .find(|(response_path, _, _)| *response_path == path)
+                    .map(|(_, status, body)| (*status, *body))
+                    .unwrap_or(("404 Not Found", ""));
+
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        webui_url
+    }
+
+    #[test]
+    fn missing_config_is_read_as_the_default_without_creating_it() {
+        let config_file_path = std::env::temp_dir()
+            .join(format!("localllm-chat-missing-config-{}.yml", std::process::id()));
+
+        let app_config = read_app_config(&config_file_path).unwrap();
+
+        assert_eq!(app_config.openwebui_image_tag, LlmChatConfig::default().openwebui_image_tag);
+        assert!(!config_file_path.exists());
+    }
+
+    #[tokio::test]
+    async fn unhealthy_status_is_not_overridden_by_fallback_endpoints() {
+        let webui_url = start_test_server(vec![
+            ("/health", "200 OK", r#"{"status": false}"#),
+            ("/", "200 OK", "<html></html>"),
+        ])
+        .await;
+
+        let health_check = check_health(&reqwest::Client::new(), &webui_url, 0).await;
+
+        assert!(!health_check.healthy);
+        assert!(health_check.json_parsed);
+        assert_eq!(health_check.endpoint.as_deref(), Some("/health"));
+    }
+
+    #[tokio::test]
+    async fn fallback_endpoints_are_checked_after_errors() {
+        let webui_url = start_test_server(vec![("/", "200 OK", "<html></html>")]).await;
+
+        let health_check = check_health(&reqwest::Client::new(), &webui_url, 0).await;
+
+        assert!(health_check.healthy);
+        assert!(!health_check.json_parsed);
+        assert_eq!(health_check.endpoint.as_deref(), Some("/"));
+    }
+}